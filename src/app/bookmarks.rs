@@ -0,0 +1,109 @@
+use ratatui::widgets::TableState;
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppResult;
+use crate::config::{read_config_file, write_config_file};
+
+const BOOKMARKS_FILE: &str = "bookmarks.toml";
+
+/// Layout of the bookmarks file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BookmarksFile {
+    /// Key paths below the root key.
+    #[serde(default)]
+    bookmarks: Vec<String>,
+}
+
+/// Key paths the user wants to get back to, shared by every hive they open.
+#[derive(Debug, Default)]
+pub struct Bookmarks {
+    pub paths: Vec<String>,
+    /// Whether the picker is open.
+    pub visible: bool,
+    /// Whether each bookmark exists in the opened hive, as of opening the picker.
+    pub present: Vec<bool>,
+    pub table_state: TableState,
+}
+
+impl Bookmarks {
+    /// Reads the bookmarks saved in the configuration directory.
+    pub fn load() -> AppResult<Self> {
+        let file: BookmarksFile = match read_config_file(BOOKMARKS_FILE)? {
+            Some(contents) => toml::from_str(&contents)?,
+            None => BookmarksFile::default(),
+        };
+        Ok(Bookmarks {
+            paths: file.bookmarks,
+            ..Default::default()
+        })
+    }
+
+    fn save(&self) -> AppResult<()> {
+        let file = BookmarksFile {
+            bookmarks: self.paths.clone(),
+        };
+        write_config_file(BOOKMARKS_FILE, &toml::to_string(&file)?)
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.paths.iter().any(|p| p.eq_ignore_ascii_case(path))
+    }
+
+    /// Bookmarks `path`, or removes the bookmark if it already exists.
+    /// Returns whether the path is bookmarked now.
+    pub fn toggle(&mut self, path: &str) -> AppResult<bool> {
+        let added = !self.contains(path);
+        if added {
+            self.paths.push(path.to_string());
+        } else {
+            self.paths.retain(|p| !p.eq_ignore_ascii_case(path));
+        }
+        self.save()?;
+        Ok(added)
+    }
+
+    /// Opens the picker, `exists` telling which bookmarks the opened hive has.
+    pub fn open(&mut self, exists: impl FnMut(&str) -> bool) {
+        self.present = self
+            .paths
+            .iter()
+            .map(|path| path.as_str())
+            .map(exists)
+            .collect();
+        self.visible = true;
+        let selected = self.table_state.selected().unwrap_or(0);
+        self.table_state
+            .select((!self.paths.is_empty()).then(|| selected.min(self.paths.len() - 1)));
+    }
+
+    pub fn selected(&self) -> Option<&String> {
+        self.paths.get(self.table_state.selected()?)
+    }
+
+    /// Removes the bookmark selected in the picker.
+    pub fn remove_selected(&mut self) -> AppResult<()> {
+        let Some(index) = self.table_state.selected() else {
+            return Ok(());
+        };
+        if index < self.paths.len() {
+            self.paths.remove(index);
+            self.present.remove(index);
+            self.save()?;
+        }
+        self.change_selection_by(0);
+        Ok(())
+    }
+
+    pub fn change_selection_by(&mut self, n: isize) {
+        if self.paths.is_empty() {
+            self.table_state.select(None);
+            return;
+        }
+        let index = self.table_state.selected().unwrap_or(0);
+        let new_index = std::cmp::min(
+            std::cmp::max(0, index as isize + n) as usize,
+            self.paths.len() - 1,
+        );
+        self.table_state.select(Some(new_index));
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::ControlFlow;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use notatin::cell_key_node::CellKeyNode;
+use notatin::cell_key_value::{CellKeyValue, CellKeyValueDataTypes};
+use notatin::parser::Parser;
+
+use crate::app::AppResult;
+use crate::export::raw_data;
+use crate::hive::walk::walk_keys;
+use crate::hive::HiveSource;
+
+/// How a key or value differs between the baseline hive and the opened one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Change {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// Type and a hash of the data of a value, which is what two values are compared by.
+type ValueData = (CellKeyValueDataTypes, u64);
+
+/// Differences between a baseline hive and the opened hive, worked out once up front.
+///
+/// Keys are matched by their path below the root key and values by their name,
+/// both ignoring case. All lookups take paths below the root key.
+#[derive(Debug)]
+pub struct Comparison {
+    pub baseline: HiveSource,
+    keys: HashMap<String, Change>,
+    values: HashMap<(String, String), Change>,
+    /// Keys that changed themselves or have changes somewhere below them.
+    changed: HashSet<String>,
+    /// Keys only the baseline has, by the path of their parent in the opened hive.
+    removed_keys: HashMap<String, Vec<CellKeyNode>>,
+    /// Values only the baseline has, by the path of their key.
+    removed_values: HashMap<String, Vec<CellKeyValue>>,
+    /// Baseline data of values that were modified.
+    baseline_values: HashMap<(String, String), CellKeyValue>,
+    /// Number of keys and values that were added, removed and modified, in that order.
+    pub counts: [usize; 3],
+}
+
+impl Comparison {
+    /// Walks both hives and records every key and value that differs.
+    pub fn new(baseline: HiveSource, parser: &mut Parser) -> AppResult<Self> {
+        let mut baseline_parser = baseline.open()?;
+        let mut comparison = Comparison {
+            baseline,
+            keys: HashMap::new(),
+            values: HashMap::new(),
+            changed: HashSet::new(),
+            removed_keys: HashMap::new(),
+            removed_values: HashMap::new(),
+            baseline_values: HashMap::new(),
+            counts: [0; 3],
+        };
+
+        // Value data of every key in the opened hive, by path.
+        let mut current: HashMap<String, HashMap<String, ValueData>> = HashMap::new();
+        let root = root_key(parser)?;
+        let _ = walk_keys(parser, root, String::new(), false, |key, path| {
+            let values = key
+                .value_iter()
+                .map(|value| (value.detail.value_name().to_lowercase(), value_data(&value)))
+                .collect();
+            current.insert(path.to_lowercase(), values);
+            ControlFlow::Continue(())
+        });
+
+        let root = root_key(&mut baseline_parser)?;
+        let _ = walk_keys(
+            &mut baseline_parser,
+            root,
+            String::new(),
+            false,
+            |key, path| {
+                comparison.compare_key(key, &path.to_lowercase(), &mut current);
+                ControlFlow::Continue(())
+            },
+        );
+
+        // Keys the baseline walk found in the opened hive were removed from `current`.
+        for path in current.into_keys() {
+            comparison.keys.insert(path, Change::Added);
+        }
+
+        let changed = comparison
+            .keys
+            .keys()
+            .chain(comparison.values.keys().map(|(path, _)| path))
+            .cloned()
+            .collect::<Vec<String>>();
+        for path in changed {
+            comparison.mark_changed(path);
+        }
+        comparison.counts = [Change::Added, Change::Removed, Change::Modified].map(|change| {
+            let changes = comparison.keys.values().chain(comparison.values.values());
+            changes.filter(|c| **c == change).count()
+        });
+        Ok(comparison)
+    }
+
+    /// Records how the baseline `key` at `path` differs from the opened hive,
+    /// and takes the key out of `current` if the opened hive has it.
+    fn compare_key(
+        &mut self,
+        key: &CellKeyNode,
+        path: &str,
+        current: &mut HashMap<String, HashMap<String, ValueData>>,
+    ) {
+        let Some(mut current_values) = current.remove(path) else {
+            self.keys.insert(path.to_string(), Change::Removed);
+            // Only keys whose parent is still there can be listed under it. Parents
+            // are visited first, so a removed parent is already known to be removed.
+            let parent = parent_path(path);
+            if self.keys.get(parent) != Some(&Change::Removed) {
+                self.removed_keys
+                    .entry(parent.to_string())
+                    .or_default()
+                    .push(key.clone());
+            }
+            return;
+        };
+
+        let mut modified = false;
+        for value in key.value_iter() {
+            let entry = (path.to_string(), value.detail.value_name().to_lowercase());
+            match current_values.remove(&entry.1) {
+                None => {
+                    self.values.insert(entry, Change::Removed);
+                    self.removed_values
+                        .entry(path.to_string())
+                        .or_default()
+                        .push(value);
+                }
+                Some(data) if data != value_data(&value) => {
+                    self.values.insert(entry.clone(), Change::Modified);
+                    self.baseline_values.insert(entry, value);
+                }
+                Some(_) => continue,
+            }
+            modified = true;
+        }
+        // Whatever is left only exists in the opened hive.
+        for name in current_values.into_keys() {
+            self.values.insert((path.to_string(), name), Change::Added);
+            modified = true;
+        }
+        if modified {
+            self.keys.insert(path.to_string(), Change::Modified);
+        }
+    }
+
+    /// Marks the key at `path` and every key above it as having changes.
+    fn mark_changed(&mut self, mut path: String) {
+        while self.changed.insert(path.clone()) && !path.is_empty() {
+            path = parent_path(&path).to_string();
+        }
+    }
+
+    /// How the key at `path` differs from the baseline.
+    pub fn key_change(&self, path: &str) -> Option<Change> {
+        self.keys.get(&path.to_lowercase()).copied()
+    }
+
+    /// Whether the key at `path` or any key below it differs from the baseline.
+    pub fn has_changes(&self, path: &str) -> bool {
+        self.changed.contains(&path.to_lowercase())
+    }
+
+    /// How the value called `name` of the key at `path` differs from the baseline.
+    /// All values of added and removed keys count as added and removed.
+    pub fn value_change(&self, path: &str, name: &str) -> Option<Change> {
+        match self.key_change(path) {
+            Some(Change::Modified) => self
+                .values
+                .get(&(path.to_lowercase(), name.to_lowercase()))
+                .copied(),
+            change => change,
+        }
+    }
+
+    /// Subkeys of the key at `path` that only the baseline has.
+    pub fn removed_keys(&self, path: &str) -> &[CellKeyNode] {
+        self.removed_keys
+            .get(&path.to_lowercase())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Values of the key at `path` that only the baseline has.
+    pub fn removed_values(&self, path: &str) -> &[CellKeyValue] {
+        self.removed_values
+            .get(&path.to_lowercase())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// The baseline data of a modified value.
+    pub fn baseline_value(&self, path: &str, name: &str) -> Option<&CellKeyValue> {
+        self.baseline_values
+            .get(&(path.to_lowercase(), name.to_lowercase()))
+    }
+}
+
+/// A [`Comparison`] being worked out on a background thread, which opens its
+/// own parsers for both hives.
+#[derive(Debug)]
+pub struct PendingComparison {
+    pub baseline: HiveSource,
+    receiver: Receiver<Result<Comparison, String>>,
+}
+
+impl PendingComparison {
+    /// Starts comparing the hive `source` describes against `baseline`.
+    pub fn start(source: &HiveSource, baseline: HiveSource) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let (source, thread_baseline) = (source.clone(), baseline.clone());
+        thread::spawn(move || {
+            let comparison = source
+                .open()
+                .and_then(|mut parser| Comparison::new(thread_baseline, &mut parser))
+                .map_err(|e| e.to_string());
+            let _ = sender.send(comparison);
+        });
+        PendingComparison { baseline, receiver }
+    }
+
+    /// The comparison, or why it failed, once the background thread is done.
+    pub fn poll(&self) -> Option<Result<Comparison, String>> {
+        match self.receiver.try_recv() {
+            Ok(comparison) => Some(comparison),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                Some(Err("comparing stopped unexpectedly".to_string()))
+            }
+        }
+    }
+}
+
+fn root_key(parser: &mut Parser) -> AppResult<CellKeyNode> {
+    Ok(parser
+        .get_root_key()
+        .ok()
+        .flatten()
+        .ok_or("hive has no root key")?)
+}
+
+fn value_data(value: &CellKeyValue) -> ValueData {
+    let mut hasher = DefaultHasher::new();
+    raw_data(&value.get_content().0).hash(&mut hasher);
+    (value.data_type, hasher.finish())
+}
+
+/// Path of the parent of the key at `path`, the root key being the parent of top level keys.
+fn parent_path(path: &str) -> &str {
+    path.rsplit_once('\\').map_or("", |(parent, _)| parent)
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use notatin::cell_value::CellValue;
+
+use crate::app::navigation::Navigation;
+
+/// Values of the system variables on a default Windows installation, used when
+/// the hive does not say otherwise.
+const DEFAULTS: &[(&str, &str)] = &[
+    ("SystemDrive", "C:"),
+    ("SystemRoot", "C:\\Windows"),
+    ("windir", "C:\\Windows"),
+    ("ProgramFiles", "C:\\Program Files"),
+    ("ProgramFiles(x86)", "C:\\Program Files (x86)"),
+    ("ProgramW6432", "C:\\Program Files"),
+    ("CommonProgramFiles", "C:\\Program Files\\Common Files"),
+    (
+        "CommonProgramFiles(x86)",
+        "C:\\Program Files (x86)\\Common Files",
+    ),
+    ("ProgramData", "C:\\ProgramData"),
+    ("ALLUSERSPROFILE", "C:\\ProgramData"),
+    ("PUBLIC", "C:\\Users\\Public"),
+];
+
+/// Keys whose values define environment variables: the system variables in a
+/// SYSTEM hive and the user variables in an NTUSER.DAT hive.
+const ENVIRONMENT_KEYS: &[&str] = &[
+    "CurrentControlSet\\Control\\Session Manager\\Environment",
+    "Environment",
+];
+
+/// Key of a SOFTWARE hive that records where Windows is installed.
+const CURRENT_VERSION_KEY: &str = "Microsoft\\Windows NT\\CurrentVersion";
+
+/// How many times variables within variable values are expanded.
+const MAX_DEPTH: usize = 8;
+
+/// Environment variables as the system the hive comes from would expand them,
+/// as far as the hive tells. Names are kept in lowercase.
+#[derive(Debug, Default)]
+pub struct Environment {
+    variables: HashMap<String, String>,
+}
+
+impl Environment {
+    /// Collects the variables defined in the hive on top of the defaults.
+    pub fn load(navigation: &mut Navigation) -> Self {
+        let mut variables: HashMap<String, String> = DEFAULTS
+            .iter()
+            .map(|(name, value)| (name.to_lowercase(), value.to_string()))
+            .collect();
+
+        if let Some((key, _)) = navigation.resolve_path(CURRENT_VERSION_KEY) {
+            if let Some(root) = key
+                .value_iter()
+                .find(|value| value.detail.value_name().eq_ignore_ascii_case("SystemRoot"))
+            {
+                if let CellValue::String(root) = root.get_content().0 {
+                    variables.insert("systemroot".to_string(), root.clone());
+                    variables.insert("windir".to_string(), root);
+                }
+            }
+        }
+        for path in ENVIRONMENT_KEYS {
+            let Some((key, _)) = navigation.resolve_path(path) else {
+                continue;
+            };
+            for value in key.value_iter() {
+                if let CellValue::String(data) = value.get_content().0 {
+                    variables.insert(value.detail.value_name().to_lowercase(), data);
+                }
+            }
+        }
+        Environment { variables }
+    }
+
+    /// Replaces `%NAME%` with the variable's value. Unknown variables are left as they are.
+    pub fn expand(&self, text: &str) -> String {
+        self.expand_to_depth(text, MAX_DEPTH)
+    }
+
+    fn expand_to_depth(&self, text: &str, depth: usize) -> String {
+        let mut expanded = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find('%') {
+            let Some(length) = rest[start + 1..].find('%') else {
+                break;
+            };
+            let name = &rest[start + 1..start + 1 + length];
+            expanded.push_str(&rest[..start]);
+            match self.variables.get(&name.to_lowercase()) {
+                Some(value) if depth > 0 => {
+                    expanded.push_str(&self.expand_to_depth(value, depth - 1));
+                    rest = &rest[start + length + 2..];
+                }
+                // Keep the closing `%`, it may open the next variable.
+                _ => {
+                    expanded.push('%');
+                    expanded.push_str(name);
+                    rest = &rest[start + length + 1..];
+                }
+            }
+        }
+        expanded.push_str(rest);
+        expanded
+    }
+}
@@ -0,0 +1,38 @@
+use crate::app::keymap::Context;
+
+/// The overlay listing the keys bound in the keymap.
+#[derive(Debug, Default)]
+pub struct Help {
+    pub visible: bool,
+    /// Where the overlay was opened, which decides the keys it lists.
+    pub context: Context,
+    /// First line shown.
+    pub scroll: usize,
+}
+
+impl Help {
+    /// Opens the overlay for the keys of `context`.
+    pub fn show(&mut self, context: Context) {
+        *self = Help {
+            visible: true,
+            context,
+            scroll: 0,
+        };
+    }
+
+    /// The contexts whose keys are listed: those of the pane or overlay the
+    /// help was opened in, and the global ones if they work there too.
+    pub fn contexts(&self) -> Vec<Context> {
+        if self.context.is_pane() {
+            vec![self.context, Context::Global]
+        } else {
+            vec![self.context]
+        }
+    }
+
+    /// Scrolls `n` lines down (or up for negative `n`). The widget keeps the
+    /// last line from scrolling out of view.
+    pub fn scroll_by(&mut self, n: isize) {
+        self.scroll = std::cmp::max(0, self.scroll as isize + n) as usize;
+    }
+}
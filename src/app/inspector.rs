@@ -0,0 +1,180 @@
+use notatin::cell_key_value::CellKeyValue;
+
+use crate::decoders::content;
+use crate::export::stored_data;
+
+/// Tracks what the value inspector shows for the selected value.
+#[derive(Debug, Default)]
+pub struct InspectorState {
+    /// Offset of the value the rest of this state refers to.
+    value_offset: usize,
+    /// Index into [`versions`] of the version being inspected, 0 being the current data.
+    pub version: usize,
+    /// Shows the raw data even when a decoder recognises the value. Kept across values.
+    pub raw: bool,
+    /// Shows `REG_EXPAND_SZ` data with its environment variables expanded. Kept across values.
+    pub expand: bool,
+    /// Shows the innermost layer of embedded content instead of the data. Kept across values.
+    pub content: bool,
+    /// Offset of the byte under the cursor of the hex view.
+    pub cursor: usize,
+    /// Offset where the selection started, if a byte range is being selected.
+    pub anchor: Option<usize>,
+    /// First row of the hex view that is shown.
+    pub scroll: usize,
+    /// Layout of the hex view the last time it was drawn, for moving by rows and pages.
+    pub bytes_per_row: usize,
+    pub page_rows: usize,
+    /// Byte sequences the last search in the hex view looks for. Kept across values.
+    pub pattern: Vec<Vec<u8>>,
+}
+
+impl InspectorState {
+    /// Resets the inspector if `value` is not the value it was last used for.
+    pub fn sync(&mut self, value: &CellKeyValue) {
+        if self.value_offset != value.file_offset_absolute {
+            *self = InspectorState {
+                value_offset: value.file_offset_absolute,
+                raw: self.raw,
+                expand: self.expand,
+                content: self.content,
+                bytes_per_row: self.bytes_per_row,
+                page_rows: self.page_rows,
+                pattern: std::mem::take(&mut self.pattern),
+                ..Default::default()
+            };
+        }
+    }
+
+    /// Selects the version `n` entries further down the list of versions, which
+    /// is one further back in time (or forward for negative `n`).
+    pub fn change_version_by(&mut self, value: &CellKeyValue, n: isize) {
+        self.sync(value);
+        let last = versions(value).len().saturating_sub(1);
+        self.version = std::cmp::min(std::cmp::max(0, self.version as isize + n) as usize, last);
+    }
+
+    /// Switches between the data and its embedded content. Offsets in one mean
+    /// nothing in the other, so the cursor and selection start over.
+    pub fn toggle_content(&mut self) {
+        self.content = !self.content;
+        self.cursor = 0;
+        self.anchor = None;
+        self.scroll = 0;
+    }
+
+    /// Moves the hex view cursor `n` bytes forward (or back for negative `n`),
+    /// staying within `len` bytes of data.
+    pub fn move_cursor_by(&mut self, len: usize, n: isize) {
+        self.cursor = std::cmp::min(
+            std::cmp::max(0, self.cursor as isize + n) as usize,
+            len.saturating_sub(1),
+        );
+    }
+
+    /// Moves the cursor `n` rows down (or up for negative `n`).
+    pub fn move_cursor_rows_by(&mut self, len: usize, n: isize) {
+        self.move_cursor_by(len, n * self.bytes_per_row.max(1) as isize);
+    }
+
+    /// Moves the cursor `n` pages down (or up for negative `n`).
+    pub fn move_cursor_pages_by(&mut self, len: usize, n: isize) {
+        self.move_cursor_rows_by(len, n * self.page_rows.max(1) as isize);
+    }
+
+    /// Starts selecting bytes at the cursor, or drops the selection.
+    pub fn toggle_selection(&mut self) {
+        self.anchor = match self.anchor {
+            Some(_) => None,
+            None => Some(self.cursor),
+        };
+    }
+
+    /// First and last offset of the selected bytes, both included.
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.anchor?;
+        Some((anchor.min(self.cursor), anchor.max(self.cursor)))
+    }
+
+    /// Sets the pattern to search for from user input. Pairs of hex digits,
+    /// optionally separated by spaces, are searched as bytes. Anything else,
+    /// or text in double quotes, is searched as ASCII and as UTF-16LE.
+    pub fn set_pattern(&mut self, input: &str) {
+        let text = input
+            .strip_prefix('"')
+            .and_then(|text| text.strip_suffix('"'));
+        self.pattern = match (text, hex_bytes(input)) {
+            (None, Some(bytes)) => vec![bytes],
+            _ => {
+                let text = text.unwrap_or(input);
+                vec![
+                    text.as_bytes().to_vec(),
+                    text.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+                ]
+            }
+        };
+    }
+
+    /// Moves the cursor to the next match of the pattern after it, or the
+    /// previous one before it if `forward` is false. A match at the cursor
+    /// counts as the next one unless `skip_cursor` is set. Returns whether there was one.
+    pub fn find(&mut self, data: &[u8], forward: bool, skip_cursor: bool) -> bool {
+        let matches = |offset: &usize| {
+            self.pattern
+                .iter()
+                .any(|pattern| !pattern.is_empty() && data[*offset..].starts_with(pattern))
+        };
+        let found = if forward {
+            (self.cursor + skip_cursor as usize..data.len()).find(matches)
+        } else {
+            (0..self.cursor.min(data.len())).rev().find(matches)
+        };
+        if let Some(offset) = found {
+            self.cursor = offset;
+        }
+        found.is_some()
+    }
+
+    /// The bytes of a value as the hex view shows them: the data itself, or the
+    /// innermost layer of embedded content when that is being shown.
+    pub fn data(&self, value: &CellKeyValue) -> Vec<u8> {
+        if self.content {
+            if let Some(inner) = content::sniff(&value.get_content().0).innermost() {
+                return inner.to_vec();
+            }
+        }
+        stored_data(value)
+    }
+}
+
+/// Parses pairs of hex digits, ignoring spaces. `None` if `input` is anything else.
+fn hex_bytes(input: &str) -> Option<Vec<u8>> {
+    let digits: Vec<char> = input.chars().filter(|c| *c != ' ').collect();
+    if digits.is_empty()
+        || !digits.len().is_multiple_of(2)
+        || !digits.iter().all(char::is_ascii_hexdigit)
+    {
+        return None;
+    }
+    digits
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(&pair.iter().collect::<String>(), 16).ok())
+        .collect()
+}
+
+/// The current value followed by its older versions recovered from transaction logs.
+pub fn versions(value: &CellKeyValue) -> Vec<&CellKeyValue> {
+    std::iter::once(value)
+        .chain(value.versions.iter())
+        .collect()
+}
+
+/// Describes where a version of a value was read from: the primary file, or
+/// the sequence number of the transaction log entry that wrote it. notatin
+/// does not tell which of the logs that entry came from.
+pub fn version_source(value: &CellKeyValue) -> String {
+    match value.sequence_num {
+        Some(sequence_num) => format!("transaction log, sequence number {}", sequence_num),
+        None => "primary file".to_string(),
+    }
+}
@@ -0,0 +1,569 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use strum::AsRefStr;
+
+use crate::app::state::FocusedPane;
+use crate::app::AppResult;
+use crate::config::read_config_file;
+
+const KEYMAP_FILE: &str = "keys.toml";
+
+/// Where a key is pressed. The panes fall back to the global bindings for
+/// keys they do not bind themselves, the overlays take every key.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Deserialize, AsRefStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Context {
+    #[default]
+    Global,
+    KeySelector,
+    ValueSelector,
+    ValueInspector,
+    Timeline,
+    Bookmarks,
+    Help,
+}
+
+impl Context {
+    pub fn title(self) -> &'static str {
+        match self {
+            Context::Global => "Everywhere",
+            Context::KeySelector => "Subkeys",
+            Context::ValueSelector => "Values",
+            Context::ValueInspector => "Value Inspector",
+            Context::Timeline => "Timeline",
+            Context::Bookmarks => "Bookmarks",
+            Context::Help => "Help",
+        }
+    }
+
+    pub fn is_pane(self) -> bool {
+        matches!(
+            self,
+            Context::KeySelector | Context::ValueSelector | Context::ValueInspector
+        )
+    }
+}
+
+impl From<&FocusedPane> for Context {
+    fn from(pane: &FocusedPane) -> Self {
+        match pane {
+            FocusedPane::KeySelector => Context::KeySelector,
+            FocusedPane::ValueSelector => Context::ValueSelector,
+            FocusedPane::ValueInspector => Context::ValueInspector,
+        }
+    }
+}
+
+/// Something a key can be bound to. What an action does depends on the
+/// context it is bound in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, AsRefStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Action {
+    Quit,
+    Back,
+    Forward,
+    NextPane,
+    PreviousPane,
+    Search,
+    GoTo,
+    Timeline,
+    ToggleBookmark,
+    Bookmarks,
+    Export,
+    CopyPath,
+    NextHit,
+    PreviousHit,
+    Help,
+    Down,
+    Up,
+    PageDown,
+    PageUp,
+    First,
+    Last,
+    Left,
+    Right,
+    Open,
+    Parent,
+    ToggleDeleted,
+    ToggleChangesOnly,
+    ToggleSecurity,
+    OlderVersion,
+    NewerVersion,
+    ToggleRaw,
+    ToggleExpand,
+    ToggleContent,
+    Select,
+    Find,
+    FindNext,
+    FindPrevious,
+    Save,
+    Close,
+    CycleOrder,
+    FilterPath,
+    FilterRange,
+    Remove,
+}
+
+impl Action {
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Back => "Go back to the previous key",
+            Action::Forward => "Go forward to the next key",
+            Action::NextPane => "Focus the next pane",
+            Action::PreviousPane => "Focus the previous pane",
+            Action::Search => "Search names and data",
+            Action::GoTo => "Go to a key by its path",
+            Action::Timeline => "Show keys by last written time",
+            Action::ToggleBookmark => "Bookmark the selected key, or remove the bookmark",
+            Action::Bookmarks => "Show the bookmarks",
+            Action::Export => "Export the selected key",
+            Action::CopyPath => "Copy the path of the selected key",
+            Action::NextHit => "Go to the next search hit",
+            Action::PreviousHit => "Go to the previous search hit",
+            Action::Help => "Show the keys",
+            Action::Down => "Move down",
+            Action::Up => "Move up",
+            Action::PageDown => "Move down a page",
+            Action::PageUp => "Move up a page",
+            Action::First => "Go to the start",
+            Action::Last => "Go to the end",
+            Action::Left => "Move left",
+            Action::Right => "Move right",
+            Action::Open => "Open the selection",
+            Action::Parent => "Go to the parent key",
+            Action::ToggleDeleted => "Show or hide deleted keys and values",
+            Action::ToggleChangesOnly => "Show only changes from the baseline, or everything",
+            Action::ToggleSecurity => "Switch between key details and security",
+            Action::OlderVersion => "Select the next older version",
+            Action::NewerVersion => "Select the next newer version",
+            Action::ToggleRaw => "Switch between raw and decoded data",
+            Action::ToggleExpand => "Expand environment variables, or stop",
+            Action::ToggleContent => "Show embedded content, or the data",
+            Action::Select => "Start or drop a selection",
+            Action::Find => "Find bytes or text",
+            Action::FindNext => "Go to the next match",
+            Action::FindPrevious => "Go to the previous match",
+            Action::Save => "Save the data to a file",
+            Action::Close => "Close",
+            Action::CycleOrder => "Change the order",
+            Action::FilterPath => "Filter by path",
+            Action::FilterRange => "Filter by time range",
+            Action::Remove => "Remove the selected bookmark",
+        }
+    }
+}
+
+/// Names of keys that are not characters.
+const KEY_NAMES: &[(&str, KeyCode)] = &[
+    ("Enter", KeyCode::Enter),
+    ("Esc", KeyCode::Esc),
+    ("Backspace", KeyCode::Backspace),
+    ("Tab", KeyCode::Tab),
+    ("Space", KeyCode::Char(' ')),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("Insert", KeyCode::Insert),
+    ("Delete", KeyCode::Delete),
+];
+
+/// Modifier prefixes, in the order they are written.
+const MODIFIER_NAMES: &[(&str, KeyModifiers)] = &[
+    ("Ctrl-", KeyModifiers::CONTROL),
+    ("Alt-", KeyModifiers::ALT),
+    ("Shift-", KeyModifiers::SHIFT),
+];
+
+/// A key with its modifiers, such as `Ctrl-j`, `Shift-Tab` or `PageDown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    /// Terminals report Shift with characters as the character itself and
+    /// Shift-Tab as a key of its own, so those are stored the same way.
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Tab | KeyCode::BackTab if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            KeyCode::Char(c) => {
+                let shift = modifiers.contains(KeyModifiers::SHIFT);
+                modifiers.remove(KeyModifiers::SHIFT);
+                // Ctrl-C and Ctrl-c are the same to most terminals.
+                if modifiers.contains(KeyModifiers::CONTROL) {
+                    KeyCode::Char(c.to_ascii_lowercase())
+                } else if shift {
+                    KeyCode::Char(c.to_ascii_uppercase())
+                } else {
+                    KeyCode::Char(c)
+                }
+            }
+            code => code,
+        };
+        Key { code, modifiers }
+    }
+}
+
+impl From<KeyEvent> for Key {
+    fn from(key_event: KeyEvent) -> Self {
+        Key::new(key_event.code, key_event.modifiers)
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        // Whatever follows the last prefix is the key, even if it is a `-`.
+        'prefixes: loop {
+            for (prefix, modifier) in MODIFIER_NAMES {
+                if rest.len() > prefix.len()
+                    && rest
+                        .get(..prefix.len())
+                        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+                {
+                    modifiers |= *modifier;
+                    rest = &rest[prefix.len()..];
+                    continue 'prefixes;
+                }
+            }
+            break;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match KEY_NAMES
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(rest))
+            {
+                Some((_, code)) => *code,
+                None => match rest
+                    .strip_prefix(['F', 'f'])
+                    .and_then(|n| n.parse::<u8>().ok())
+                {
+                    Some(n @ 1..=24) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key {:?}", s)),
+                },
+            },
+        };
+        Ok(Key::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (prefix, modifier) in MODIFIER_NAMES {
+            if self.modifiers.contains(*modifier) {
+                write!(f, "{}", prefix)?;
+            }
+        }
+        match self.code {
+            KeyCode::BackTab => write!(f, "Shift-Tab"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => match KEY_NAMES.iter().find(|(_, named)| *named == code) {
+                Some((name, _)) => write!(f, "{}", name),
+                None => match code {
+                    KeyCode::Char(c) => write!(f, "{}", c),
+                    code => write!(f, "{:?}", code),
+                },
+            },
+        }
+    }
+}
+
+/// The keys bound to each action unless the keymap file says otherwise. This
+/// also lists which actions each context has.
+const DEFAULTS: &[(Context, Action, &[&str])] = &[
+    (Context::Global, Action::Quit, &["q", "Esc", "Ctrl-c"]),
+    // Terminals send Ctrl-i as Tab, so going forward again has no Ctrl binding.
+    (Context::Global, Action::Back, &["Ctrl-o", "Alt-Left"]),
+    (Context::Global, Action::Forward, &["Alt-Right"]),
+    (Context::Global, Action::NextPane, &["Tab"]),
+    (Context::Global, Action::PreviousPane, &["Shift-Tab"]),
+    (Context::Global, Action::Search, &["/"]),
+    (Context::Global, Action::GoTo, &["g"]),
+    (Context::Global, Action::Timeline, &["t"]),
+    (Context::Global, Action::ToggleBookmark, &["b"]),
+    (Context::Global, Action::Bookmarks, &["B"]),
+    (Context::Global, Action::Export, &["e"]),
+    (Context::Global, Action::CopyPath, &["y"]),
+    (Context::Global, Action::NextHit, &["n"]),
+    (Context::Global, Action::PreviousHit, &["N"]),
+    (Context::Global, Action::Help, &["?"]),
+    (Context::KeySelector, Action::Down, &["j", "Down"]),
+    (Context::KeySelector, Action::Up, &["k", "Up"]),
+    (
+        Context::KeySelector,
+        Action::PageDown,
+        &["Ctrl-j", "PageDown"],
+    ),
+    (Context::KeySelector, Action::PageUp, &["Ctrl-k", "PageUp"]),
+    (Context::KeySelector, Action::First, &["Home"]),
+    (Context::KeySelector, Action::Last, &["End"]),
+    (Context::KeySelector, Action::Open, &["l", "Right", "Enter"]),
+    (
+        Context::KeySelector,
+        Action::Parent,
+        &["h", "Left", "Backspace"],
+    ),
+    (Context::KeySelector, Action::ToggleDeleted, &["D"]),
+    (Context::KeySelector, Action::ToggleChangesOnly, &["C"]),
+    (Context::KeySelector, Action::ToggleSecurity, &["s"]),
+    (Context::ValueSelector, Action::Down, &["j", "Down"]),
+    (Context::ValueSelector, Action::Up, &["k", "Up"]),
+    (
+        Context::ValueSelector,
+        Action::PageDown,
+        &["Ctrl-j", "PageDown"],
+    ),
+    (
+        Context::ValueSelector,
+        Action::PageUp,
+        &["Ctrl-k", "PageUp"],
+    ),
+    (Context::ValueSelector, Action::First, &["Home"]),
+    (Context::ValueSelector, Action::Last, &["End"]),
+    (Context::ValueSelector, Action::Open, &["Enter"]),
+    (Context::ValueInspector, Action::OlderVersion, &["]"]),
+    (Context::ValueInspector, Action::NewerVersion, &["["]),
+    (Context::ValueInspector, Action::ToggleRaw, &["r"]),
+    (Context::ValueInspector, Action::ToggleExpand, &["x"]),
+    (Context::ValueInspector, Action::ToggleContent, &["c"]),
+    (Context::ValueInspector, Action::Down, &["j", "Down"]),
+    (Context::ValueInspector, Action::Up, &["k", "Up"]),
+    (
+        Context::ValueInspector,
+        Action::PageDown,
+        &["Ctrl-j", "Ctrl-Down", "PageDown"],
+    ),
+    (
+        Context::ValueInspector,
+        Action::PageUp,
+        &["Ctrl-k", "Ctrl-Up", "PageUp"],
+    ),
+    (Context::ValueInspector, Action::Left, &["h", "Left"]),
+    (Context::ValueInspector, Action::Right, &["l", "Right"]),
+    (Context::ValueInspector, Action::First, &["0", "Home"]),
+    (Context::ValueInspector, Action::Last, &["$", "End"]),
+    (Context::ValueInspector, Action::Select, &["v"]),
+    (Context::ValueInspector, Action::Find, &["f"]),
+    (Context::ValueInspector, Action::FindNext, &[";"]),
+    (Context::ValueInspector, Action::FindPrevious, &[","]),
+    (Context::ValueInspector, Action::Save, &["s"]),
+    (Context::Timeline, Action::Close, &["Esc", "t"]),
+    (Context::Timeline, Action::Quit, &["q", "Ctrl-c"]),
+    (Context::Timeline, Action::Open, &["Enter"]),
+    (Context::Timeline, Action::Down, &["j", "Down"]),
+    (Context::Timeline, Action::Up, &["k", "Up"]),
+    (Context::Timeline, Action::PageDown, &["Ctrl-j", "PageDown"]),
+    (Context::Timeline, Action::PageUp, &["Ctrl-k", "PageUp"]),
+    (Context::Timeline, Action::CycleOrder, &["o"]),
+    (Context::Timeline, Action::FilterPath, &["f"]),
+    (Context::Timeline, Action::FilterRange, &["r"]),
+    (Context::Timeline, Action::Help, &["?"]),
+    (Context::Bookmarks, Action::Close, &["Esc", "B"]),
+    (Context::Bookmarks, Action::Quit, &["q", "Ctrl-c"]),
+    (Context::Bookmarks, Action::Open, &["Enter"]),
+    (Context::Bookmarks, Action::Down, &["j", "Down"]),
+    (Context::Bookmarks, Action::Up, &["k", "Up"]),
+    (Context::Bookmarks, Action::Remove, &["d"]),
+    (Context::Bookmarks, Action::Help, &["?"]),
+    (Context::Help, Action::Close, &["Esc", "?", "q"]),
+    (Context::Help, Action::Down, &["j", "Down"]),
+    (Context::Help, Action::Up, &["k", "Up"]),
+    (Context::Help, Action::PageDown, &["Ctrl-j", "PageDown"]),
+    (Context::Help, Action::PageUp, &["Ctrl-k", "PageUp"]),
+];
+
+/// One key or a list of them, as written in the keymap file.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Keys {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Layout of the keymap file: a table per context, mapping action names to
+/// the keys that replace their default ones, such as `down = ["j", "Ctrl-n"]`
+/// under `[key_selector]`.
+type KeymapFile = HashMap<Context, HashMap<Action, Keys>>;
+
+/// Which keys trigger which actions in each context.
+#[derive(Debug)]
+pub struct Keymap {
+    bindings: Vec<(Context, Action, Vec<Key>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = DEFAULTS
+            .iter()
+            .map(|(context, action, keys)| {
+                let keys = keys
+                    .iter()
+                    .map(|key| key.parse().expect("default keys are valid"))
+                    .collect();
+                (*context, *action, keys)
+            })
+            .collect();
+        Keymap { bindings }
+    }
+}
+
+impl Keymap {
+    /// The default keymap with the bindings of the keymap file in the
+    /// configuration directory applied.
+    pub fn load() -> AppResult<Self> {
+        let mut keymap = Keymap::default();
+        if let Some(contents) = read_config_file(KEYMAP_FILE)? {
+            let file: KeymapFile =
+                toml::from_str(&contents).map_err(|e| format!("{}: {}", KEYMAP_FILE, e))?;
+            keymap.apply(file)?;
+        }
+        Ok(keymap)
+    }
+
+    /// Binds the keys of `file` instead of the defaults of their actions. A
+    /// key taken over from another action of the same context is unbound there.
+    fn apply(&mut self, file: KeymapFile) -> AppResult<()> {
+        for (context, actions) in file {
+            for (action, keys) in actions {
+                let keys = match keys {
+                    Keys::One(key) => vec![key],
+                    Keys::Many(keys) => keys,
+                };
+                let keys = keys
+                    .iter()
+                    .map(|key| key.parse())
+                    .collect::<Result<Vec<Key>, String>>()
+                    .map_err(|e| format!("{}: {}", KEYMAP_FILE, e))?;
+                for (bound_context, _, bound_keys) in &mut self.bindings {
+                    if *bound_context == context {
+                        bound_keys.retain(|key| !keys.contains(key));
+                    }
+                }
+                let (_, _, bound_keys) = self
+                    .bindings
+                    .iter_mut()
+                    .find(|(c, a, _)| *c == context && *a == action)
+                    .ok_or_else(|| {
+                        format!(
+                            "{}: there is no {} in [{}]",
+                            KEYMAP_FILE,
+                            action.as_ref(),
+                            context.as_ref()
+                        )
+                    })?;
+                *bound_keys = keys;
+            }
+        }
+        Ok(())
+    }
+
+    /// The action `key_event` triggers in `context`, if any.
+    pub fn action(&self, context: Context, key_event: KeyEvent) -> Option<Action> {
+        let key = Key::from(key_event);
+        self.bindings
+            .iter()
+            .find(|(c, _, keys)| *c == context && keys.contains(&key))
+            .map(|(_, action, _)| *action)
+    }
+
+    /// Whether `key` triggers an action in `context`.
+    pub fn binds(&self, context: Context, key: &Key) -> bool {
+        self.bindings(context).any(|(_, keys)| keys.contains(key))
+    }
+
+    /// The first key bound to `action` in `context`, to point it out in hints.
+    pub fn first_key(&self, context: Context, action: Action) -> Option<Key> {
+        self.bindings(context)
+            .find(|(a, _)| *a == action)
+            .and_then(|(_, keys)| keys.first().copied())
+    }
+
+    /// The actions of `context` with the keys bound to them, in a fixed order.
+    pub fn bindings(&self, context: Context) -> impl Iterator<Item = (Action, &[Key])> {
+        self.bindings
+            .iter()
+            .filter(move |(c, _, _)| *c == context)
+            .map(|(_, action, keys)| (*action, keys.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(s: &str) -> Key {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn default_keys_round_trip() {
+        for (_, _, keys) in DEFAULTS {
+            for s in *keys {
+                assert_eq!(key(s).to_string(), *s);
+                assert_eq!(key(&key(s).to_string()), key(s));
+            }
+        }
+    }
+
+    #[test]
+    fn named_and_modified_keys_round_trip() {
+        for s in [
+            "Ctrl-Alt-Delete",
+            "Alt-F12",
+            "Ctrl--",
+            "-",
+            "Space",
+            "Ctrl-Space",
+            "é",
+        ] {
+            assert_eq!(key(s).to_string(), s);
+        }
+    }
+
+    #[test]
+    fn parses_keys_the_way_terminals_report_them() {
+        assert_eq!(key("ctrl-C"), key("Ctrl-c"));
+        assert_eq!(key("Shift-a"), key("A"));
+        assert_eq!(key("shift-tab"), key("Shift-Tab"));
+        assert_eq!(key("f5"), key("F5"));
+        assert_eq!(
+            key("Shift-Tab"),
+            Key::from(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT))
+        );
+        assert_eq!(
+            key("N"),
+            Key::from(KeyEvent::new(KeyCode::Char('N'), KeyModifiers::SHIFT))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        for s in ["", "Ctrl-", "F0", "F25", "Hyper-x", "jj"] {
+            assert!(s.parse::<Key>().is_err(), "{:?} parsed", s);
+        }
+    }
+}
@@ -1,11 +1,22 @@
 use std::error;
 
+pub mod bookmarks;
+pub mod compare;
+pub mod environment;
+pub mod help;
+pub mod inspector;
+pub mod keymap;
+pub mod mouse;
 pub mod navigation;
+pub mod prompt;
+pub mod search;
 pub mod state;
+pub mod timeline;
 
 use notatin::parser::Parser;
 
 use crate::app::state::State;
+use crate::hive::HiveSource;
 
 /// Application result type.
 pub type AppResult<T> = std::result::Result<T, Box<dyn error::Error>>;
@@ -20,15 +31,31 @@ pub struct App {
 
 impl App {
     /// Constructs a new instance of [`App`].
-    pub fn new(parser: Parser) -> Self {
-        App {
+    pub fn new(parser: Parser, source: HiveSource) -> AppResult<Self> {
+        Ok(App {
             running: true,
-            state: State::new(parser),
-        }
+            state: State::new(parser, source)?,
+        })
     }
 
     /// Handles the tick event of the terminal.
-    pub fn tick(&self) {}
+    pub fn tick(&mut self) {
+        self.state.start_pending_search();
+        self.state.search.poll();
+        self.state.timeline.poll();
+        if let Err(e) = self.state.navigation.poll_comparison() {
+            self.state.message = Some(format!("comparison failed: {}", e));
+        }
+        // Jump to the first hit as soon as the background search finds it,
+        // unless the user has moved on since starting the search.
+        let search = &self.state.search;
+        if search.current.is_none()
+            && !search.hits.is_empty()
+            && search.origin == self.state.navigation.location()
+        {
+            self.state.change_search_hit_by(1);
+        }
+    }
 
     /// Set running to false to quit the application.
     pub fn quit(&mut self) {
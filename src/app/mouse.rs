@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+use ratatui::layout::{Margin, Position, Rect};
+
+use crate::app::state::FocusedPane;
+
+/// Longest time between two clicks on the same cell that makes them a double click.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+/// Where the interface was drawn the last time, recorded while rendering.
+/// Areas of widgets that were not drawn stay empty, so nothing hits them.
+#[derive(Debug, Default)]
+pub struct HitAreas {
+    pub key_selector: Rect,
+    pub value_selector: Rect,
+    pub value_inspector: Rect,
+    /// Breadcrumb segments and the paths of the keys they stand for.
+    pub breadcrumb: Vec<(Rect, String)>,
+}
+
+/// What the mouse pointer is over.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    /// A row of the subkey or value list, counted from the first row shown.
+    Row(FocusedPane, usize),
+    /// Anywhere else in a pane, including its border.
+    Pane(FocusedPane),
+    /// A breadcrumb segment, with the path of its key.
+    Breadcrumb(String),
+}
+
+#[derive(Debug, Default)]
+pub struct MouseState {
+    pub areas: HitAreas,
+    /// Time and cell of the last left click.
+    last_click: Option<(Instant, Position)>,
+}
+
+impl MouseState {
+    /// What is drawn at `column` and `row` of the screen.
+    pub fn target(&self, column: u16, row: u16) -> Option<Target> {
+        let position = Position { x: column, y: row };
+        if let Some((_, path)) = self
+            .areas
+            .breadcrumb
+            .iter()
+            .find(|(area, _)| area.contains(position))
+        {
+            return Some(Target::Breadcrumb(path.clone()));
+        }
+
+        let panes = [
+            (FocusedPane::KeySelector, self.areas.key_selector),
+            (FocusedPane::ValueSelector, self.areas.value_selector),
+            (FocusedPane::ValueInspector, self.areas.value_inspector),
+        ];
+        let (pane, area) = panes
+            .into_iter()
+            .find(|(_, area)| area.contains(position))?;
+        // The lists fill their pane inside its border, one row per line.
+        let rows = area.inner(Margin::new(1, 1));
+        if pane != FocusedPane::ValueInspector && rows.contains(position) {
+            return Some(Target::Row(pane, (row - rows.y) as usize));
+        }
+        Some(Target::Pane(pane))
+    }
+
+    /// Records a left click at `column` and `row`, returning whether it
+    /// completes a double click.
+    pub fn click(&mut self, column: u16, row: u16) -> bool {
+        let position = Position { x: column, y: row };
+        let now = Instant::now();
+        let double = self.last_click.is_some_and(|(time, last)| {
+            last == position && now.duration_since(time) <= DOUBLE_CLICK_TIME
+        });
+        // A third click starts over rather than making another double click.
+        self.last_click = (!double).then_some((now, position));
+        double
+    }
+}
@@ -3,9 +3,13 @@ use std::num::NonZero;
 use lru::LruCache;
 use notatin::cell_key_node::CellKeyNode;
 use notatin::cell_key_value::CellKeyValue;
+use notatin::cell_value::CellValue;
 use notatin::parser::Parser;
 use ratatui::widgets::TableState;
 
+use crate::app::compare::{Change, Comparison, PendingComparison};
+use crate::hive::{key_path, HiveSource};
+
 /// Contains and manages information about where we are currently
 /// located within the registry hive tree structure.
 #[derive(Debug)]
@@ -19,10 +23,43 @@ pub struct Navigation {
     pub table_states: CurrentKeyState,
     pub key_state_cache: LruCache<usize, TableState>,
     pub value_state_cache: LruCache<usize, TableState>,
+    /// List deleted keys and values recovered by the parser alongside the allocated ones.
+    pub show_deleted: bool,
+    /// Differences to a baseline hive, shown in the subkey and value lists.
+    pub comparison: Option<Comparison>,
+    /// Comparison with a baseline hive that is still being worked out.
+    pub pending_comparison: Option<PendingComparison>,
+    /// Parser of the baseline hive, opened the first time a key only the
+    /// baseline has is opened.
+    baseline_parser: Option<Parser>,
+    /// Only list keys and values that differ from the baseline.
+    pub changes_only: bool,
+    /// Keys visited before the current one, most recent last.
+    pub back_history: Vec<HistoryEntry>,
+    /// Keys left by going back, most recent last.
+    pub forward_history: Vec<HistoryEntry>,
+}
+
+/// Number of keys remembered for going back.
+const HISTORY_LENGTH: usize = 100;
+
+/// A visited key along with what was selected in it.
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub key: CellKeyNode,
+    pub table_states: CurrentKeyState,
+}
+
+/// The open key and what is selected in it, for telling whether the user moved.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Location {
+    key: usize,
+    subkey: Option<usize>,
+    value: Option<usize>,
 }
 
 impl Navigation {
-    pub fn new(mut parser: Parser) -> Self {
+    pub fn new(mut parser: Parser, show_deleted: bool) -> Self {
         let current_key = parser
             .get_root_key()
             .expect("This hive to have a root key")
@@ -38,6 +75,13 @@ impl Navigation {
             selected_value: None,
             key_state_cache: LruCache::new(NonZero::new(200).unwrap()),
             value_state_cache: LruCache::new(NonZero::new(200).unwrap()),
+            show_deleted,
+            comparison: None,
+            pending_comparison: None,
+            baseline_parser: None,
+            changes_only: false,
+            back_history: vec![],
+            forward_history: vec![],
         }
         .with_selected_key(current_key.clone())
     }
@@ -47,7 +91,79 @@ impl Navigation {
         self
     }
 
+    /// Opens `key`, remembering the current key for going back if it is a different one.
     pub fn select_key(&mut self, key: CellKeyNode) {
+        if key.file_offset_absolute != self.current_key.file_offset_absolute {
+            let entry = self.history_entry();
+            if self.back_history.len() == HISTORY_LENGTH {
+                self.back_history.remove(0);
+            }
+            self.back_history.push(entry);
+            self.forward_history.clear();
+        }
+        self.open_key(key);
+    }
+
+    pub fn location(&self) -> Location {
+        Location {
+            key: self.current_key.file_offset_absolute,
+            subkey: self
+                .selected_subkey
+                .as_ref()
+                .map(|key| key.file_offset_absolute),
+            value: self
+                .selected_value
+                .as_ref()
+                .map(|value| value.file_offset_absolute),
+        }
+    }
+
+    fn history_entry(&self) -> HistoryEntry {
+        HistoryEntry {
+            key: self.current_key.clone(),
+            table_states: self.table_states.clone(),
+        }
+    }
+
+    /// Returns to the key visited before the current one. Returns whether there was one.
+    pub fn go_back(&mut self) -> bool {
+        let Some(entry) = self.back_history.pop() else {
+            return false;
+        };
+        self.forward_history.push(self.history_entry());
+        self.restore(entry);
+        true
+    }
+
+    /// Undoes a [`Navigation::go_back`]. Returns whether there was anything to undo.
+    pub fn go_forward(&mut self) -> bool {
+        let Some(entry) = self.forward_history.pop() else {
+            return false;
+        };
+        self.back_history.push(self.history_entry());
+        self.restore(entry);
+        true
+    }
+
+    /// Opens a key from the history and selects the subkey and value selected back then.
+    fn restore(&mut self, entry: HistoryEntry) {
+        self.open_key(entry.key);
+        let states = entry.table_states;
+
+        let subkey_index = states.key_selector_state.selected();
+        self.table_states.key_selector_state = states.key_selector_state;
+        self.select_subkey(subkey_index.and_then(|index| self.current_subkeys.get(index).cloned()));
+
+        if let Some(index) = states.value_selector_state.selected() {
+            if let Some(value) = self.current_values.get(index) {
+                self.selected_value = Some(value.clone());
+                self.table_states.value_selector_state = states.value_selector_state;
+            }
+        }
+    }
+
+    /// Shows `key` without touching the history.
+    fn open_key(&mut self, key: CellKeyNode) {
         // Save this key/value selection states in LRU caches in case we navigate back to this point
         // Only do this if the key/value selection is in a non-default state so that we don't
         // needlessly fill the LRU cache with default states.
@@ -71,9 +187,19 @@ impl Navigation {
         }
 
         // Update the current members
+        let mut key = key;
+        self.current_subkeys = self.subkeys_of(&mut key);
         self.current_key = key;
-        self.current_subkeys = self.current_key.read_sub_keys(&mut self.parser);
-        self.current_values = self.current_key.value_iter().collect::<Vec<CellKeyValue>>();
+        if let Some(comparison) = &self.comparison {
+            let path = self.current_path();
+            self.current_subkeys
+                .extend(comparison.removed_keys(&path).iter().cloned());
+            if self.changes_only {
+                self.current_subkeys
+                    .retain(|subkey| comparison.has_changes(&key_path::relative(&subkey.path)));
+            }
+        }
+        self.current_values = self.values_of(&self.current_key);
 
         // Get the saved table states for this key, or initialize new ones if they don't exist
         self.table_states.key_selector_state = self
@@ -90,6 +216,197 @@ impl Navigation {
         );
     }
 
+    /// Looks up a key by its backslash-separated path below the root key.
+    /// Path components are compared case-insensitively, as Windows does.
+    pub fn find_key(&mut self, path: &str) -> Option<CellKeyNode> {
+        let mut key = self.parser.get_root_key().ok()??;
+        for name in path.split('\\').filter(|name| !name.is_empty()) {
+            key = self
+                .subkeys_of(&mut key)
+                .into_iter()
+                .find(|subkey| subkey.key_name.eq_ignore_ascii_case(name))?;
+        }
+        Some(key)
+    }
+
+    /// Resolves a path typed by the user to a key and its path below the root key,
+    /// spelled the way the hive spells it.
+    ///
+    /// Accepts forms copied from regedit or reports, such as `HKLM\SOFTWARE\Microsoft`,
+    /// and follows `CurrentControlSet` to the control set marked current in `Select`.
+    pub fn resolve_path(&mut self, path: &str) -> Option<(CellKeyNode, String)> {
+        let mut key = self.parser.get_root_key().ok()??;
+        let mut components = key_path::normalize(path, &key.key_name);
+        if let Some(index) = components
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case("CurrentControlSet"))
+        {
+            if let Some(control_set) = self.current_control_set() {
+                components[index] = control_set;
+            }
+        }
+
+        let mut resolved = vec![];
+        for name in components {
+            key = self
+                .subkeys_of(&mut key)
+                .into_iter()
+                .find(|subkey| subkey.key_name.eq_ignore_ascii_case(&name))?;
+            resolved.push(key.key_name.clone());
+        }
+        Some((key, resolved.join("\\")))
+    }
+
+    /// Shows the key at a user supplied path, see [`Navigation::resolve_path`].
+    pub fn go_to(&mut self, path: &str) -> bool {
+        match self.resolve_path(path) {
+            Some((_, resolved)) => self.reveal(&resolved, None),
+            None => false,
+        }
+    }
+
+    /// Completes the last component of a partially typed path, which may also be
+    /// a root key or the name the hive is mounted under (see [`key_path::prefix_completions`]).
+    ///
+    /// Returns the completed input, extended to the longest prefix shared by all
+    /// matching names, along with those names.
+    pub fn complete_path(&mut self, input: &str, mount_point: &str) -> (String, Vec<String>) {
+        let (head, partial) = match input.rfind('\\') {
+            Some(index) => input.split_at(index + 1),
+            None => ("", input),
+        };
+        let (mut candidates, keys) = key_path::prefix_completions(head, mount_point);
+        if keys {
+            if let Some((mut parent, _)) = self.resolve_path(head) {
+                candidates.extend(
+                    self.subkeys_of(&mut parent)
+                        .into_iter()
+                        .map(|subkey| subkey.key_name),
+                );
+            }
+        }
+        candidates.retain(|name| name.to_lowercase().starts_with(&partial.to_lowercase()));
+
+        match candidates.as_slice() {
+            [] => (input.to_string(), candidates),
+            [only] => (format!("{}{}\\", head, only), vec![]),
+            [first, rest @ ..] => {
+                let common = rest.iter().fold(first.clone(), |common, name| {
+                    common
+                        .chars()
+                        .zip(name.chars())
+                        .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+                        .map(|(a, _)| a)
+                        .collect()
+                });
+                let completed = if common.len() > partial.len() {
+                    format!("{}{}", head, common)
+                } else {
+                    input.to_string()
+                };
+                (completed, candidates)
+            }
+        }
+    }
+
+    /// Name of the control set `CurrentControlSet` links to in a SYSTEM hive.
+    fn current_control_set(&mut self) -> Option<String> {
+        let select = self.find_key("Select")?;
+        let current = select
+            .value_iter()
+            .find(|value| value.detail.value_name().eq_ignore_ascii_case("Current"))?;
+        match current.get_content().0 {
+            CellValue::U32(number) => Some(format!("ControlSet{:03}", number)),
+            CellValue::I32(number) => Some(format!("ControlSet{:03}", number)),
+            _ => None,
+        }
+    }
+
+    /// The subkeys of `key`, followed by its recovered deleted subkeys if those are shown.
+    /// Subkeys of keys only the baseline has are read through the baseline's parser.
+    fn subkeys_of(&mut self, key: &mut CellKeyNode) -> Vec<CellKeyNode> {
+        if self.in_baseline_only(key) {
+            return self.baseline_subkeys(key);
+        }
+        let mut subkeys = key.read_sub_keys(&mut self.parser);
+        if self.show_deleted {
+            subkeys.extend(key.deleted_keys.iter().cloned());
+        }
+        subkeys
+    }
+
+    /// The subkeys of a key only the baseline has, all of which only the
+    /// baseline has as well.
+    fn baseline_subkeys(&mut self, key: &mut CellKeyNode) -> Vec<CellKeyNode> {
+        if self.baseline_parser.is_none() {
+            let comparison = self.comparison.as_ref();
+            self.baseline_parser = comparison.and_then(|c| c.baseline.open().ok());
+        }
+        match &mut self.baseline_parser {
+            Some(parser) => key.read_sub_keys(parser),
+            None => vec![],
+        }
+    }
+
+    /// Whether `key` is one only the baseline has, read from the baseline hive.
+    pub fn in_baseline_only(&self, key: &CellKeyNode) -> bool {
+        self.key_change(&key_path::relative(&key.path)) == Some(Change::Removed)
+    }
+
+    /// Selects the key at `path` in the subkey list of its parent, and the value
+    /// called `value_name` among its values. Returns whether the key was found.
+    pub fn reveal(&mut self, path: &str, value_name: Option<&str>) -> bool {
+        let (parent_path, name) = path.rsplit_once('\\').unwrap_or(("", path));
+        if name.is_empty() {
+            // The root key has no parent to select it in, so open it instead.
+            match self.find_key("") {
+                Some(root) => self.select_key(root),
+                None => return false,
+            }
+            return true;
+        }
+
+        let Some(parent) = self.find_key(parent_path) else {
+            return false;
+        };
+        self.select_key(parent);
+        let Some(index) = self
+            .current_subkeys
+            .iter()
+            .position(|subkey| subkey.key_name.eq_ignore_ascii_case(name))
+        else {
+            return false;
+        };
+        self.table_states.key_selector_state.select(Some(index));
+        self.select_subkey(self.current_subkeys.get(index).cloned());
+
+        if let Some(index) = value_name.and_then(|value_name| {
+            self.current_values
+                .iter()
+                .position(|value| value.detail.value_name() == value_name)
+        }) {
+            self.table_states.value_selector_state.select(Some(index));
+            self.selected_value = self.current_values.get(index).cloned();
+        }
+        true
+    }
+
+    /// Path of the current key below the root key.
+    pub fn current_path(&self) -> String {
+        key_path::relative(&self.current_key.path)
+    }
+
+    /// Path of the selected subkey below the root key, or of the current key if
+    /// it has no subkeys.
+    pub fn selected_path(&self) -> String {
+        key_path::relative(&self.selected_key().path)
+    }
+
+    /// The selected subkey, or the current key if it has no subkeys.
+    pub fn selected_key(&self) -> &CellKeyNode {
+        self.selected_subkey.as_ref().unwrap_or(&self.current_key)
+    }
+
     pub fn enter_key(&mut self) {
         if let Some(subkey) = &self.selected_subkey {
             self.select_key(subkey.clone());
@@ -97,11 +414,86 @@ impl Navigation {
     }
 
     pub fn leave_key(&mut self) {
-        if let Ok(parent_key) = self.parser.get_parent_key(&mut self.current_key) {
-            if let Some(key) = parent_key {
-                self.select_key(key.clone());
+        let parent = if self.in_baseline_only(&self.current_key) {
+            // The parent of a key only the baseline has is taken from the opened
+            // hive unless the baseline is the only one having it as well.
+            let path = self.current_path();
+            let parent_path = path.rsplit_once('\\').map_or("", |(parent, _)| parent);
+            if self.key_change(parent_path) == Some(Change::Removed) {
+                let parser = self.baseline_parser.as_mut();
+                parser.and_then(|parser| parser.get_parent_key(&mut self.current_key).ok()?)
+            } else {
+                self.find_key(parent_path)
+            }
+        } else {
+            self.parser
+                .get_parent_key(&mut self.current_key)
+                .ok()
+                .flatten()
+        };
+        if let Some(key) = parent {
+            self.select_key(key);
+        }
+    }
+
+    /// Shows or hides recovered deleted keys and values, staying on the current key.
+    pub fn toggle_deleted(&mut self) {
+        self.show_deleted = !self.show_deleted;
+        self.select_key(self.current_key.clone());
+    }
+
+    /// The values of `key`, leaving out recovered deleted values unless those are shown,
+    /// followed by the values only the baseline has when comparing.
+    fn values_of(&self, key: &CellKeyNode) -> Vec<CellKeyValue> {
+        let mut values = key
+            .value_iter()
+            .filter(|value| self.show_deleted || !value.cell_state.is_deleted())
+            .collect::<Vec<CellKeyValue>>();
+        if let Some(comparison) = &self.comparison {
+            let path = key_path::relative(&key.path);
+            values.extend(comparison.removed_values(&path).iter().cloned());
+            if self.changes_only {
+                values.retain(|value| {
+                    comparison
+                        .value_change(&path, &value.detail.value_name())
+                        .is_some()
+                });
             }
         }
+        values
+    }
+
+    /// Starts comparing the hive `source` describes against `baseline` in the
+    /// background. See [`Navigation::poll_comparison`].
+    pub fn compare_with(&mut self, source: &HiveSource, baseline: HiveSource) {
+        self.pending_comparison = Some(PendingComparison::start(source, baseline));
+    }
+
+    /// Starts marking the differences to the baseline once the comparison is
+    /// done, keeping the selection. Returns why the comparison failed, if it did.
+    pub fn poll_comparison(&mut self) -> Result<(), String> {
+        let Some(result) = self
+            .pending_comparison
+            .as_ref()
+            .and_then(PendingComparison::poll)
+        else {
+            return Ok(());
+        };
+        self.pending_comparison = None;
+        self.comparison = Some(result?);
+        self.restore(self.history_entry());
+        Ok(())
+    }
+
+    /// How the key at `path` differs from the baseline, when comparing.
+    pub fn key_change(&self, path: &str) -> Option<Change> {
+        self.comparison.as_ref()?.key_change(path)
+    }
+
+    /// Shows only keys and values that differ from the baseline, or everything again.
+    pub fn toggle_changes_only(&mut self) {
+        self.changes_only = !self.changes_only;
+        self.select_key(self.current_key.clone());
     }
 
     pub fn select_subkey(&mut self, key: Option<CellKeyNode>) {
@@ -118,7 +510,7 @@ impl Navigation {
 
         match self.selected_subkey {
             Some(ref sk) => {
-                self.current_values = sk.value_iter().collect::<Vec<CellKeyValue>>();
+                self.current_values = self.values_of(sk);
 
                 self.table_states.value_selector_state = self
                     .value_state_cache
@@ -126,10 +518,12 @@ impl Navigation {
                     .unwrap_or(&TableState::default())
                     .clone();
                 if let Some(index) = self.table_states.value_selector_state.selected() {
-                    self.selected_value = Some(self.current_values[index].clone());
+                    self.selected_value = self.current_values.get(index).cloned();
                 } else if !self.current_values.is_empty() {
                     self.selected_value = Some(self.current_values[0].clone());
                     self.table_states.value_selector_state.select(Some(0));
+                } else {
+                    self.selected_value = None;
                 }
             }
             None => {
@@ -148,16 +542,16 @@ impl Navigation {
 
         let new_index = std::cmp::min(
             std::cmp::max(0, index as isize + n_keys) as usize,
-            self.current_key
-                .cell_sub_key_offsets_absolute
-                .len()
-                .checked_sub(1)
-                .unwrap_or(0),
+            self.current_subkeys.len().saturating_sub(1),
         );
 
-        self.table_states.key_selector_state.select(Some(new_index));
+        self.select_subkey_at(new_index);
+    }
 
-        self.select_subkey(self.current_subkeys.get(new_index).cloned());
+    /// Selects the subkey at `index` in the subkey list.
+    pub fn select_subkey_at(&mut self, index: usize) {
+        self.table_states.key_selector_state.select(Some(index));
+        self.select_subkey(self.current_subkeys.get(index).cloned());
     }
 
     pub fn change_value_by(&mut self, n_keys: isize) {
@@ -167,17 +561,20 @@ impl Navigation {
             .selected()
             .unwrap_or(0);
 
-        if let Some(subkey) = &self.selected_subkey {
+        if self.selected_subkey.is_some() {
             let new_index = std::cmp::min(
                 std::cmp::max(0, index as isize + n_keys) as usize,
-                subkey.value_iter().count().checked_sub(1).unwrap_or(0),
+                self.current_values.len().saturating_sub(1),
             );
-            self.table_states
-                .value_selector_state
-                .select(Some(new_index));
-            self.selected_value = self.current_values.get(new_index).cloned();
+            self.select_value_at(new_index);
         }
     }
+
+    /// Selects the value at `index` in the value list.
+    pub fn select_value_at(&mut self, index: usize) {
+        self.table_states.value_selector_state.select(Some(index));
+        self.selected_value = self.current_values.get(index).cloned();
+    }
 }
 
 #[derive(Default, Debug, Clone)]
@@ -0,0 +1,53 @@
+/// What a line of user input is for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptKind {
+    Search,
+    GoTo,
+    /// File name to export the selected key to.
+    Export,
+    /// Substring the paths in the timeline have to contain.
+    TimelinePath,
+    /// Time range of the keys in the timeline.
+    TimelineRange,
+    /// Bytes or text to find in the data of the selected value.
+    FindInValue,
+    /// File name to save the data of the selected value to.
+    SaveValue,
+}
+
+/// A single line of text input shown at the bottom of the screen.
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub input: String,
+    /// Candidates offered by the last tab completion.
+    pub completions: Vec<String>,
+}
+
+impl Prompt {
+    pub fn new(kind: PromptKind) -> Self {
+        Prompt::with_input(kind, String::new())
+    }
+
+    /// A prompt with `input` already filled in, to be edited or accepted.
+    pub fn with_input(kind: PromptKind, input: String) -> Self {
+        Prompt {
+            kind,
+            input,
+            completions: vec![],
+        }
+    }
+
+    /// Text shown in front of the input.
+    pub fn label(&self) -> &'static str {
+        match self.kind {
+            PromptKind::Search => "/",
+            PromptKind::GoTo => "go to: ",
+            PromptKind::Export => "export to: ",
+            PromptKind::TimelinePath => "path contains: ",
+            PromptKind::TimelineRange => "written between (UTC, FROM..TO): ",
+            PromptKind::FindInValue => "find in value (hex bytes or text): ",
+            PromptKind::SaveValue => "save data to (.b64/.hex for text): ",
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+
+use notatin::cell_key_node::CellKeyNode;
+use notatin::cell_value::CellValue;
+use regex::{Regex, RegexBuilder};
+
+use crate::app::navigation::Location;
+use crate::hive::walk::walk_keys;
+use crate::hive::HiveSource;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchOptions {
+    /// Treat the query as a regular expression instead of a literal string.
+    pub regex: bool,
+    pub case_sensitive: bool,
+}
+
+/// Which part of a key or value matched the query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HitKind {
+    KeyName,
+    ValueName,
+    ValueData,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    /// Path of the key below the root key.
+    pub key_path: String,
+    /// Name of the matching value, if the hit is a value.
+    pub value_name: Option<String>,
+    pub kind: HitKind,
+}
+
+#[derive(Debug)]
+enum SearchMessage {
+    Hit(SearchHit),
+    Done,
+    Failed(String),
+}
+
+/// Walks the whole hive on a background thread looking for a query.
+///
+/// The thread opens its own parser over the same files, so the UI keeps
+/// working while hits trickle in through [`Search::poll`].
+#[derive(Debug, Default)]
+pub struct Search {
+    pub query: String,
+    pub options: SearchOptions,
+    pub hits: Vec<SearchHit>,
+    /// Index of the hit we last jumped to.
+    pub current: Option<usize>,
+    pub running: bool,
+    pub error: Option<String>,
+    /// Query typed into the search prompt that has not been searched for yet.
+    pub pending: Option<String>,
+    /// Where the user was when the search started. The first hit is only
+    /// jumped to if they are still there.
+    pub origin: Location,
+    receiver: Option<Receiver<SearchMessage>>,
+    /// Tells the thread of the running search to stop.
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Search {
+    /// Starts searching for `query`, abandoning any search still running.
+    /// An empty query only stops the running search; an invalid one is
+    /// reported through [`Search::error`].
+    pub fn start(&mut self, source: &HiveSource, query: String, origin: Location) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        *self = Search {
+            query,
+            options: self.options,
+            origin,
+            ..Default::default()
+        };
+        if self.query.is_empty() {
+            return;
+        }
+
+        let pattern = if self.options.regex {
+            self.query.clone()
+        } else {
+            regex::escape(&self.query)
+        };
+        let matcher = match RegexBuilder::new(&pattern)
+            .case_insensitive(!self.options.case_sensitive)
+            .build()
+        {
+            Ok(matcher) => matcher,
+            Err(e) => {
+                self.error = Some(format!("invalid search: {}", e));
+                return;
+            }
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let source = source.clone();
+        let cancelled = self.cancelled.clone();
+        thread::spawn(move || {
+            let message = match source.open() {
+                Ok(mut parser) => match parser.get_root_key() {
+                    Ok(Some(root)) => {
+                        let _ = walk_keys(
+                            &mut parser,
+                            root,
+                            String::new(),
+                            source.recover_deleted,
+                            |key, path| {
+                                if cancelled.load(Ordering::Relaxed) {
+                                    return ControlFlow::Break(());
+                                }
+                                for hit in match_key(&matcher, key, path, source.recover_deleted) {
+                                    if sender.send(SearchMessage::Hit(hit)).is_err() {
+                                        return ControlFlow::Break(());
+                                    }
+                                }
+                                ControlFlow::Continue(())
+                            },
+                        );
+                        SearchMessage::Done
+                    }
+                    _ => SearchMessage::Failed("the hive has no root key".to_string()),
+                },
+                Err(e) => SearchMessage::Failed(e.to_string()),
+            };
+            let _ = sender.send(message);
+        });
+
+        self.running = true;
+        self.receiver = Some(receiver);
+    }
+
+    /// Collects the hits found since the last call.
+    pub fn poll(&mut self) {
+        let Some(receiver) = &self.receiver else {
+            return;
+        };
+
+        loop {
+            match receiver.try_recv() {
+                Ok(SearchMessage::Hit(hit)) => self.hits.push(hit),
+                Ok(SearchMessage::Done) | Err(TryRecvError::Disconnected) => {
+                    self.running = false;
+                    break;
+                }
+                Ok(SearchMessage::Failed(e)) => {
+                    self.running = false;
+                    self.error = Some(e);
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+            }
+        }
+
+        if !self.running {
+            self.receiver = None;
+        }
+    }
+
+    /// Moves `n` hits forward (or backward for negative `n`), wrapping around,
+    /// and returns the new current hit.
+    pub fn change_hit_by(&mut self, n: isize) -> Option<&SearchHit> {
+        if self.hits.is_empty() {
+            return None;
+        }
+        let count = self.hits.len() as isize;
+        let index = match self.current {
+            Some(index) => (index as isize + n).rem_euclid(count) as usize,
+            None if n < 0 => self.hits.len() - 1,
+            None => 0,
+        };
+        self.current = Some(index);
+        self.hits.get(index)
+    }
+}
+
+fn match_key(
+    matcher: &Regex,
+    key: &CellKeyNode,
+    path: &str,
+    include_deleted: bool,
+) -> Vec<SearchHit> {
+    let mut hits = vec![];
+    if matcher.is_match(&key.key_name) {
+        hits.push(SearchHit {
+            key_path: path.to_string(),
+            value_name: None,
+            kind: HitKind::KeyName,
+        });
+    }
+
+    let values = key
+        .value_iter()
+        .filter(|value| include_deleted || !value.cell_state.is_deleted())
+        .collect::<Vec<_>>();
+    for value in values {
+        let kind = if matcher.is_match(&value.detail.value_name()) {
+            HitKind::ValueName
+        } else {
+            match value.get_content().0 {
+                CellValue::String(s) if matcher.is_match(&s) => HitKind::ValueData,
+                CellValue::MultiString(strings) if strings.iter().any(|s| matcher.is_match(s)) => {
+                    HitKind::ValueData
+                }
+                _ => continue,
+            }
+        };
+        hits.push(SearchHit {
+            key_path: path.to_string(),
+            value_name: Some(value.detail.value_name()),
+            kind,
+        });
+    }
+    hits
+}
@@ -1,8 +1,26 @@
+use crate::app::bookmarks::Bookmarks;
+use crate::app::environment::Environment;
+use crate::app::help::Help;
+use crate::app::inspector::{self, InspectorState};
+use crate::app::keymap::{Context, Keymap};
+use crate::app::mouse::MouseState;
 use crate::app::navigation::Navigation;
+use crate::app::prompt::Prompt;
+use crate::app::search::{HitKind, Search};
+use crate::app::timeline::Timeline;
+use crate::app::AppResult;
+use crate::decoders::content::FlagCache;
+use crate::decoders::timestamp::DisplayTimezone;
+use crate::export::data::{sha256, write_data, DataFormat};
+use crate::export::{export, ExportFormat, ExportScope};
+use crate::hive::{HiveSource, LogReplay};
 use notatin::parser::Parser;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::Path;
 use strum::{EnumIter, IntoEnumIterator};
 
-#[derive(Debug, Default, EnumIter, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, EnumIter, PartialEq)]
 pub enum FocusedPane {
     #[default]
     KeySelector,
@@ -10,17 +28,248 @@ pub enum FocusedPane {
     ValueInspector,
 }
 
+/// What the pane below the subkey list shows about the selected subkey.
+#[derive(Debug, Default, PartialEq)]
+pub enum KeyPreview {
+    #[default]
+    Details,
+    Security,
+}
+
 #[derive(Debug)]
 pub struct State {
     pub navigation: Navigation,
     pub focused_pane: FocusedPane,
+    pub key_preview: KeyPreview,
+    pub inspector: InspectorState,
+    pub source: HiveSource,
+    pub log_replay: LogReplay,
+    /// Line of input currently being typed, if any.
+    pub prompt: Option<Prompt>,
+    pub search: Search,
+    /// Keys by last written time, shown instead of the panes while visible.
+    pub timeline: Timeline,
+    pub bookmarks: Bookmarks,
+    /// Which keys trigger which actions, from the keymap file.
+    pub keymap: Keymap,
+    pub help: Help,
+    /// Timezone times decoded from values are shown in.
+    pub timezone: DisplayTimezone,
+    /// Environment variables of the hive, collected the first time a value is expanded.
+    pub environment: Option<Environment>,
+    /// Content flags of the listed values, see [`FlagCache`].
+    pub content_flags: FlagCache,
+    /// Where things were drawn, for telling what mouse events point at.
+    pub mouse: MouseState,
+    /// Transient message for the status bar, cleared on the next key press.
+    pub message: Option<String>,
 }
 
 impl State {
-    pub fn new(parser: Parser) -> Self {
-        State {
-            navigation: Navigation::new(parser),
+    pub fn new(parser: Parser, source: HiveSource) -> AppResult<Self> {
+        let log_replay = LogReplay::from_parser(&source, &parser);
+        let (bookmarks, message) = match Bookmarks::load() {
+            Ok(bookmarks) => (bookmarks, None),
+            Err(e) => (Bookmarks::default(), Some(format!("bookmarks: {}", e))),
+        };
+        let (keymap, message) = match Keymap::load() {
+            Ok(keymap) => (keymap, message),
+            Err(e) => (Keymap::default(), Some(format!("keymap: {}", e))),
+        };
+        Ok(State {
+            navigation: Navigation::new(parser, source.recover_deleted),
             focused_pane: FocusedPane::default(),
+            key_preview: KeyPreview::default(),
+            inspector: InspectorState::default(),
+            source,
+            log_replay,
+            prompt: None,
+            search: Search::default(),
+            timeline: Timeline::default(),
+            bookmarks,
+            keymap,
+            help: Help::default(),
+            timezone: DisplayTimezone::default(),
+            environment: None,
+            content_flags: FlagCache::default(),
+            mouse: MouseState::default(),
+            message,
+        })
+    }
+
+    /// Looks for `input` in the data of the selected value, starting at the hex view cursor.
+    /// See [`InspectorState::set_pattern`] for how the input is read.
+    pub fn find_in_value(&mut self, input: &str) {
+        self.inspector.set_pattern(input);
+        self.find_next_in_value(true, false);
+    }
+
+    /// Moves the hex view cursor to the next or previous match of the last pattern.
+    pub fn find_next_in_value(&mut self, forward: bool, skip_cursor: bool) {
+        let Some(value) = &self.navigation.selected_value else {
+            return;
+        };
+        if self.inspector.pattern.is_empty() {
+            self.message = Some("nothing to find yet, press f to search the value".to_string());
+            return;
+        }
+        self.inspector.sync(value);
+        let data = self.inspector.data(value);
+        if !self.inspector.find(&data, forward, skip_cursor) {
+            self.message = Some("no more matches in this value".to_string());
+        }
+    }
+
+    /// Starts or stops showing `REG_EXPAND_SZ` data with its variables expanded.
+    pub fn toggle_expand(&mut self) {
+        self.inspector.expand = !self.inspector.expand;
+        if self.inspector.expand && self.environment.is_none() {
+            self.environment = Some(Environment::load(&mut self.navigation));
+        }
+    }
+
+    pub fn toggle_key_preview(&mut self) {
+        self.key_preview = match self.key_preview {
+            KeyPreview::Details => KeyPreview::Security,
+            KeyPreview::Security => KeyPreview::Details,
+        };
+    }
+
+    /// Starts searching for the query typed into the search prompt since the last call.
+    pub fn start_pending_search(&mut self) {
+        if let Some(query) = self.search.pending.take() {
+            let origin = self.navigation.location();
+            self.search.start(&self.source, query, origin);
+        }
+    }
+
+    /// Moves `n` search hits forward or backward and shows the hit's key and value.
+    pub fn change_search_hit_by(&mut self, n: isize) {
+        let Some(hit) = self.search.change_hit_by(n).cloned() else {
+            return;
+        };
+        if !self
+            .navigation
+            .reveal(&hit.key_path, hit.value_name.as_deref())
+        {
+            self.message = Some(format!("cannot open {}", hit.key_path));
+            return;
+        }
+        self.focused_pane = match hit.kind {
+            HitKind::KeyName => FocusedPane::KeySelector,
+            HitKind::ValueName | HitKind::ValueData => FocusedPane::ValueSelector,
+        };
+    }
+
+    /// Closes the timeline and shows the key selected in it.
+    pub fn open_timeline_entry(&mut self) {
+        let Some(entry) = self.timeline.selected().cloned() else {
+            return;
+        };
+        if self.navigation.reveal(&entry.key_path, None) {
+            self.timeline.visible = false;
+            self.focused_pane = FocusedPane::KeySelector;
+        } else {
+            self.message = Some(format!("cannot open {}", entry.key_path));
+        }
+    }
+
+    /// Bookmarks the selected key, or removes its bookmark.
+    pub fn toggle_bookmark(&mut self) {
+        let path = self.navigation.selected_path();
+        self.message = Some(match self.bookmarks.toggle(&path) {
+            Ok(true) => format!("bookmarked {}", path),
+            Ok(false) => format!("removed bookmark {}", path),
+            Err(e) => format!("cannot save bookmarks: {}", e),
+        });
+    }
+
+    pub fn open_bookmarks(&mut self) {
+        let navigation = &mut self.navigation;
+        self.bookmarks
+            .open(|path| navigation.find_key(path).is_some());
+    }
+
+    /// Closes the bookmark picker and shows the key selected in it.
+    pub fn open_selected_bookmark(&mut self) {
+        let Some(path) = self.bookmarks.selected().cloned() else {
+            return;
+        };
+        if self.navigation.reveal(&path, None) {
+            self.bookmarks.visible = false;
+            self.focused_pane = FocusedPane::KeySelector;
+        } else {
+            self.message = Some(format!("{} is not in this hive", path));
+        }
+    }
+
+    /// Writes the selected key and its descendants to `file`, in the format
+    /// its extension names.
+    pub fn export_selected_key(&mut self, file: &Path) -> AppResult<()> {
+        let format = ExportFormat::from_path(file)
+            .ok_or("unknown format, use a .reg, .json, .jsonl or .csv file name")?;
+        let key = self.navigation.selected_key().clone();
+        if self.navigation.in_baseline_only(&key) {
+            return Err("keys only in the baseline hive cannot be exported".into());
+        }
+        let path = self.navigation.selected_path();
+        let scope = ExportScope {
+            path: &path,
+            mount_point: &self.source.mount_point,
+            include_deleted: self.navigation.show_deleted,
+        };
+        let mut out = BufWriter::new(File::create(file)?);
+        let keys = export(format, &mut self.navigation.parser, key, &scope, &mut out)?;
+        out.flush()?;
+        self.message = Some(format!("exported {} keys to {}", keys, file.display()));
+        Ok(())
+    }
+
+    /// Writes the data of the inspected value to `file`, or just the selected bytes
+    /// if a range is selected in the hex view. The extension picks the format,
+    /// see [`DataFormat::from_path`]. Reports the SHA-256 of the bytes written.
+    /// An existing file is left alone, since it may be evidence itself.
+    pub fn save_selected_value(&mut self, file: &Path) -> AppResult<()> {
+        let value = self
+            .navigation
+            .selected_value
+            .as_ref()
+            .ok_or("no value selected")?;
+        self.inspector.sync(value);
+        let versions = inspector::versions(value);
+        let version = versions[self.inspector.version.min(versions.len() - 1)];
+        let mut data = self.inspector.data(version);
+        // The hex view, and with it the selection, only shows the current data.
+        if let (0, Some((start, end))) = (self.inspector.version, self.inspector.selection()) {
+            data = data.get(start..=end).unwrap_or_default().to_vec();
+        }
+
+        let created = OpenOptions::new().write(true).create_new(true).open(file);
+        let mut out = BufWriter::new(created.map_err(|e| match e.kind() {
+            ErrorKind::AlreadyExists => format!("{} already exists", file.display()),
+            _ => e.to_string(),
+        })?);
+        write_data(DataFormat::from_path(file), &data, &mut out)?;
+        out.flush()?;
+        self.message = Some(format!(
+            "saved {} bytes to {}, SHA-256 {}",
+            data.len(),
+            file.display(),
+            sha256(&data)
+        ));
+        Ok(())
+    }
+
+    /// Where keys are looked up in the keymap: the overlay on top, or the focused pane.
+    pub fn key_context(&self) -> Context {
+        if self.help.visible {
+            Context::Help
+        } else if self.bookmarks.visible {
+            Context::Bookmarks
+        } else if self.timeline.visible {
+            Context::Timeline
+        } else {
+            Context::from(&self.focused_pane)
         }
     }
 
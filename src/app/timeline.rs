@@ -0,0 +1,254 @@
+use std::ops::ControlFlow;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use ratatui::widgets::TableState;
+
+use crate::app::AppResult;
+use crate::hive::walk::walk_keys;
+use crate::hive::HiveSource;
+
+#[derive(Debug, Clone)]
+pub struct TimelineEntry {
+    /// Path of the key below the root key.
+    pub key_path: String,
+    pub last_written: DateTime<Utc>,
+}
+
+/// How the timeline is sorted.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TimelineOrder {
+    #[default]
+    NewestFirst,
+    OldestFirst,
+    Path,
+}
+
+impl TimelineOrder {
+    pub fn next(self) -> Self {
+        match self {
+            TimelineOrder::NewestFirst => TimelineOrder::OldestFirst,
+            TimelineOrder::OldestFirst => TimelineOrder::Path,
+            TimelineOrder::Path => TimelineOrder::NewestFirst,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TimelineOrder::NewestFirst => "newest first",
+            TimelineOrder::OldestFirst => "oldest first",
+            TimelineOrder::Path => "by path",
+        }
+    }
+}
+
+/// Restricts the timeline to keys written within a time range and matching a path.
+#[derive(Debug, Clone, Default)]
+pub struct TimelineFilter {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Case-insensitive substring the key path has to contain.
+    pub path: String,
+}
+
+impl TimelineFilter {
+    fn matches(&self, entry: &TimelineEntry) -> bool {
+        self.from.is_none_or(|from| entry.last_written >= from)
+            && self.to.is_none_or(|to| entry.last_written <= to)
+            && entry
+                .key_path
+                .to_lowercase()
+                .contains(&self.path.to_lowercase())
+    }
+
+    /// Sets the time range from `FROM..TO`, where either end may be left out.
+    /// A lone time without `..` selects the day, minute or second it names.
+    pub fn set_range(&mut self, range: &str) -> AppResult<()> {
+        let range = range.trim();
+        (self.from, self.to) = match range.split_once("..") {
+            Some((from, to)) => (parse_time(from, false)?, parse_time(to, true)?),
+            None => (parse_time(range, false)?, parse_time(range, true)?),
+        };
+        Ok(())
+    }
+
+    /// The time range in the form [`TimelineFilter::set_range`] accepts.
+    pub fn range(&self) -> String {
+        let format = |time: Option<DateTime<Utc>>| {
+            time.map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default()
+        };
+        match (self.from, self.to) {
+            (None, None) => String::new(),
+            (from, to) => format!("{}..{}", format(from), format(to)),
+        }
+    }
+}
+
+/// Parses a UTC time given down to the day, minute or second. Times that leave
+/// out the smaller units stand for the start of the period they name, or for
+/// its end if `end` is set. An empty string means no limit.
+fn parse_time(time: &str, end: bool) -> AppResult<Option<DateTime<Utc>>> {
+    let time = time.trim().replace('T', " ");
+    if time.is_empty() {
+        return Ok(None);
+    }
+    let (parsed, period) =
+        if let Ok(parsed) = NaiveDateTime::parse_from_str(&time, "%Y-%m-%d %H:%M:%S") {
+            (parsed, chrono::Duration::seconds(1))
+        } else if let Ok(parsed) = NaiveDateTime::parse_from_str(&time, "%Y-%m-%d %H:%M") {
+            (parsed, chrono::Duration::minutes(1))
+        } else if let Ok(parsed) = NaiveDate::parse_from_str(&time, "%Y-%m-%d") {
+            (
+                parsed.and_hms_opt(0, 0, 0).unwrap(),
+                chrono::Duration::days(1),
+            )
+        } else {
+            return Err(format!("cannot read {} as YYYY-MM-DD [HH:MM[:SS]]", time).into());
+        };
+
+    let parsed = parsed.and_utc();
+    if end {
+        Ok(Some(parsed + period - chrono::Duration::nanoseconds(1)))
+    } else {
+        Ok(Some(parsed))
+    }
+}
+
+#[derive(Debug)]
+enum TimelineMessage {
+    Done(Vec<TimelineEntry>),
+    Failed(String),
+}
+
+/// Every key of the hive with its last written time, collected on a background
+/// thread the first time the timeline is shown.
+#[derive(Debug, Default)]
+pub struct Timeline {
+    pub visible: bool,
+    pub entries: Vec<TimelineEntry>,
+    /// Indices into `entries` of the keys passing the filter, in display order.
+    pub shown: Vec<usize>,
+    pub order: TimelineOrder,
+    pub filter: TimelineFilter,
+    pub table_state: TableState,
+    pub loading: bool,
+    pub error: Option<String>,
+    receiver: Option<Receiver<TimelineMessage>>,
+}
+
+impl Timeline {
+    /// Shows or hides the timeline, collecting the key times the first time it is shown.
+    pub fn toggle(&mut self, source: &HiveSource) {
+        self.visible = !self.visible;
+        if self.visible && self.entries.is_empty() && !self.loading {
+            self.start(source);
+        }
+    }
+
+    fn start(&mut self, source: &HiveSource) {
+        let (sender, receiver) = mpsc::channel();
+        let source = source.clone();
+        thread::spawn(move || {
+            let message = match source.open() {
+                Ok(mut parser) => match parser.get_root_key() {
+                    Ok(Some(root)) => {
+                        let mut entries = vec![];
+                        let _ = walk_keys(
+                            &mut parser,
+                            root,
+                            String::new(),
+                            source.recover_deleted,
+                            |key, path| {
+                                entries.push(TimelineEntry {
+                                    key_path: path.to_string(),
+                                    last_written: key.last_key_written_date_and_time(),
+                                });
+                                ControlFlow::Continue(())
+                            },
+                        );
+                        TimelineMessage::Done(entries)
+                    }
+                    _ => TimelineMessage::Failed("the hive has no root key".to_string()),
+                },
+                Err(e) => TimelineMessage::Failed(e.to_string()),
+            };
+            let _ = sender.send(message);
+        });
+
+        self.loading = true;
+        self.error = None;
+        self.receiver = Some(receiver);
+    }
+
+    /// Picks up the key times once the background thread has collected them.
+    pub fn poll(&mut self) {
+        let Some(receiver) = &self.receiver else {
+            return;
+        };
+        match receiver.try_recv() {
+            Ok(TimelineMessage::Done(entries)) => {
+                self.entries = entries;
+                self.refresh();
+            }
+            Ok(TimelineMessage::Failed(e)) => self.error = Some(e),
+            Err(TryRecvError::Disconnected) => {
+                self.error = Some("collecting key times stopped unexpectedly".to_string())
+            }
+            Err(TryRecvError::Empty) => return,
+        }
+        self.loading = false;
+        self.receiver = None;
+    }
+
+    /// Reapplies the filter and the sort order, keeping the selected key if it is still shown.
+    pub fn refresh(&mut self) {
+        let selected = self.selected().map(|entry| entry.key_path.clone());
+
+        self.shown = (0..self.entries.len())
+            .filter(|index| self.filter.matches(&self.entries[*index]))
+            .collect();
+        let entries = &self.entries;
+        match self.order {
+            TimelineOrder::NewestFirst => self
+                .shown
+                .sort_by_key(|index| std::cmp::Reverse(entries[*index].last_written)),
+            TimelineOrder::OldestFirst => {
+                self.shown.sort_by_key(|index| entries[*index].last_written)
+            }
+            TimelineOrder::Path => self
+                .shown
+                .sort_by_key(|index| entries[*index].key_path.to_lowercase()),
+        }
+
+        let index = selected
+            .and_then(|path| {
+                self.shown
+                    .iter()
+                    .position(|index| self.entries[*index].key_path == path)
+            })
+            .unwrap_or(0);
+        self.table_state
+            .select((!self.shown.is_empty()).then_some(index));
+    }
+
+    pub fn cycle_order(&mut self) {
+        self.order = self.order.next();
+        self.refresh();
+    }
+
+    pub fn selected(&self) -> Option<&TimelineEntry> {
+        let index = self.shown.get(self.table_state.selected()?)?;
+        self.entries.get(*index)
+    }
+
+    pub fn change_selection_by(&mut self, n: isize) {
+        let index = self.table_state.selected().unwrap_or(0);
+        let new_index = std::cmp::min(
+            std::cmp::max(0, index as isize + n) as usize,
+            self.shown.len().saturating_sub(1),
+        );
+        self.table_state.select(Some(new_index));
+    }
+}
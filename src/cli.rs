@@ -0,0 +1,122 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::decoders::timestamp::DisplayTimezone;
+use crate::export::ExportFormat;
+
+/// A terminal viewer for Windows registry hives.
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// The hive to view. Always present unless a subcommand is given.
+    #[command(flatten)]
+    pub hive: Option<HiveArgs>,
+
+    /// Key to start at, as a backslash-separated path below the root key.
+    /// Prefixes such as `HKLM\SOFTWARE\` are ignored.
+    #[arg(short, long, value_name = "PATH")]
+    pub key: Option<String>,
+
+    /// Older copy of the hive to compare against. Keys and values added, removed
+    /// or modified since then are marked.
+    #[arg(long, value_name = "BASELINE")]
+    pub compare: Option<PathBuf>,
+
+    /// Timezone to show times decoded from values in: `UTC`, `local`,
+    /// an offset such as `+02:00` or a name such as `Europe/Berlin`.
+    #[arg(long, value_name = "TZ", default_value_t = DisplayTimezone::Utc)]
+    pub timezone: DisplayTimezone,
+
+    /// How to present the hive.
+    #[arg(short, long, value_enum, default_value_t = OutputMode::Tui)]
+    pub mode: OutputMode,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Write a key and all of its descendants to a file without starting the interface.
+    Export(ExportArgs),
+}
+
+/// Which hive to open and how to parse it.
+#[derive(Debug, Args)]
+pub struct HiveArgs {
+    /// Path to the registry hive file to open.
+    pub hive: PathBuf,
+
+    /// Transaction log (.LOG1/.LOG2) to replay on top of the hive.
+    /// May be given more than once. Logs next to the hive are picked up automatically.
+    #[arg(short, long = "log", value_name = "PATH")]
+    pub logs: Vec<PathBuf>,
+
+    /// Do not look for transaction logs next to the hive.
+    #[arg(long)]
+    pub no_log_discovery: bool,
+
+    /// Recover deleted keys and values from unallocated cells.
+    /// The hive file itself is never modified.
+    #[arg(short = 'd', long)]
+    pub recover_deleted: bool,
+
+    /// Key the hive is placed under in exported `.reg` files. Defaults to
+    /// `HKEY_CURRENT_USER` for NTUSER.DAT, `HKEY_CURRENT_USER\Software\Classes`
+    /// for UsrClass.dat, and `HKEY_LOCAL_MACHINE\` followed by the file name otherwise.
+    #[arg(long, value_name = "KEY")]
+    pub mount_point: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    #[command(flatten)]
+    pub hive: HiveArgs,
+
+    /// Key to export, as a backslash-separated path below the root key.
+    /// Defaults to the whole hive.
+    #[arg(short, long, value_name = "PATH")]
+    pub key: Option<String>,
+
+    /// Format to write. Defaults to the one matching the output file's extension,
+    /// or JSON Lines when writing to standard output.
+    #[arg(short, long, value_enum)]
+    pub format: Option<ExportFormat>,
+
+    /// File to write to instead of standard output.
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputMode {
+    /// Interactive terminal user interface.
+    #[default]
+    Tui,
+    /// Print the subkeys and values of the starting key and exit.
+    Text,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn options_do_not_clash() {
+        Cli::command().debug_assert();
+        let cli = Cli::parse_from(["hiview", "-m", "text", "NTUSER.DAT"]);
+        assert_eq!(cli.mode, OutputMode::Text);
+        let cli = Cli::parse_from(["hiview", "export", "-o", "out.reg", "NTUSER.DAT"]);
+        match cli.command {
+            Some(Command::Export(args)) => assert_eq!(args.output, Some("out.reg".into())),
+            None => panic!("export not parsed"),
+        }
+    }
+}
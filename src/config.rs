@@ -0,0 +1,31 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::app::AppResult;
+
+/// Path of a file in the per-user configuration directory, such as
+/// `~/.config/hiview/<name>` on Linux. `None` if the platform has no such directory.
+pub fn config_file(name: &str) -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("hiview").join(name))
+}
+
+/// Reads a configuration file, `None` if it does not exist yet.
+pub fn read_config_file(name: &str) -> AppResult<Option<String>> {
+    let Some(path) = config_file(name) else {
+        return Ok(None);
+    };
+    match fs::read_to_string(&path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("cannot read {}: {}", path.display(), e).into()),
+    }
+}
+
+/// Writes a configuration file, creating the configuration directory if needed.
+pub fn write_config_file(name: &str, contents: &str) -> AppResult<()> {
+    let path = config_file(name).ok_or("no configuration directory on this system")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, contents).map_err(|e| format!("cannot write {}: {}", path.display(), e).into())
+}
@@ -0,0 +1,35 @@
+use notatin::cell_value::CellValue;
+
+use crate::decoders::{binary, format_filetime, path_matches, Decoder, Field};
+use crate::hive::read_u64;
+
+/// Key paths of the Background and Desktop Activity Moderator entries, one key per user SID.
+const PATTERNS: &[&str] = &[
+    "*\\Services\\bam\\State\\UserSettings\\*",
+    "*\\Services\\bam\\UserSettings\\*",
+    "*\\Services\\dam\\State\\UserSettings\\*",
+    "*\\Services\\dam\\UserSettings\\*",
+];
+
+/// When a program, named by the value, was last run by the user the key belongs to.
+pub struct Bam;
+
+impl Decoder for Bam {
+    fn name(&self) -> &'static str {
+        "BAM"
+    }
+
+    fn matches(&self, key_path: &str, _value_name: &str) -> bool {
+        PATTERNS
+            .iter()
+            .any(|pattern| path_matches(key_path, pattern))
+    }
+
+    fn decode(&self, value_name: &str, data: &CellValue) -> Option<Vec<Field>> {
+        let data = binary(data)?;
+        Some(vec![
+            Field::new("Program", value_name),
+            Field::new("Last run", format_filetime(read_u64(data, 0)?)),
+        ])
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use lazy_static::lazy_static;
+use notatin::cell_value::CellValue;
+use regex::bytes::Regex;
+
+use crate::export::raw_data;
+use crate::hive::read_u32;
+use crate::hive::utf16_string;
+
+/// Bytes of a value that are looked at, so that huge values do not stall drawing.
+const MAX_INPUT: usize = 1 << 20;
+/// Bytes a compressed layer may expand to.
+const MAX_DECOMPRESSED: u64 = 4 << 20;
+/// Layers peeled off at most, in case one decodes to another indefinitely.
+const MAX_LAYERS: usize = 6;
+/// URLs or addresses listed at most.
+const MAX_FINDINGS: usize = 20;
+/// Shortest base64 text taken for an encoded blob rather than a word.
+const MIN_BASE64_LENGTH: usize = 20;
+/// Shortest run of printable characters searched for URLs and addresses.
+const MIN_STRING_LENGTH: usize = 6;
+
+lazy_static! {
+    static ref ENCODED_COMMAND: Regex = Regex::new(
+        r#"(?i)\b(?:powershell|pwsh)(?:\.exe)?\b.*?\s[-/]e[a-z]*\s+['"]?([a-z0-9+/]{8,}={0,2})"#
+    )
+    .unwrap();
+    static ref URL: Regex = Regex::new(r#"(?i)\b(?:https?|ftp)://[^\s"'<>]+"#).unwrap();
+    static ref IP_ADDRESS: Regex = Regex::new(
+        r"\b(?:(?:25[0-5]|2[0-4][0-9]|1?[0-9]?[0-9])\.){3}(?:25[0-5]|2[0-4][0-9]|1?[0-9]?[0-9])\b"
+    )
+    .unwrap();
+}
+
+/// An encoding or container found in value data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContentKind {
+    /// A Windows executable, starting at its MZ header.
+    Executable,
+    Base64,
+    /// A PowerShell command line with a base64 encoded UTF-16 script.
+    PowerShell,
+    Gzip,
+    Zlib,
+    /// UTF-16LE text, decoded to UTF-8.
+    Utf16,
+}
+
+impl ContentKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ContentKind::Executable => "executable",
+            ContentKind::Base64 => "base64",
+            ContentKind::PowerShell => "PowerShell encoded command",
+            ContentKind::Gzip => "gzip",
+            ContentKind::Zlib => "zlib",
+            ContentKind::Utf16 => "UTF-16 text",
+        }
+    }
+
+    /// Short form for the flag column, `None` for encodings too common to point out.
+    fn flag(self) -> Option<&'static str> {
+        match self {
+            ContentKind::Executable => Some("PE"),
+            ContentKind::Base64 => Some("B64"),
+            ContentKind::PowerShell => Some("PS"),
+            ContentKind::Gzip => Some("GZ"),
+            ContentKind::Zlib => Some("ZL"),
+            ContentKind::Utf16 => None,
+        }
+    }
+}
+
+/// One decoding step and the bytes it produced.
+#[derive(Debug, Clone)]
+pub struct Layer {
+    pub kind: ContentKind,
+    pub data: Vec<u8>,
+}
+
+/// What was found in a value's data.
+#[derive(Debug, Clone, Default)]
+pub struct Sniffed {
+    /// Decoding steps from the data inwards.
+    pub layers: Vec<Layer>,
+    pub urls: Vec<String>,
+    pub addresses: Vec<String>,
+}
+
+impl Sniffed {
+    /// Short flags for everything found, such as `B64 PS URL`.
+    pub fn flags(&self) -> String {
+        let mut flags: Vec<&str> = self
+            .layers
+            .iter()
+            .filter_map(|layer| layer.kind.flag())
+            .collect();
+        if !self.urls.is_empty() {
+            flags.push("URL");
+        }
+        if !self.addresses.is_empty() {
+            flags.push("IP");
+        }
+        flags.join(" ")
+    }
+
+    /// The bytes of the innermost layer, if anything was decoded.
+    pub fn innermost(&self) -> Option<&[u8]> {
+        self.layers.last().map(|layer| layer.data.as_slice())
+    }
+}
+
+/// [`Sniffed::flags`] of the values of one key, sniffed once when the key is
+/// shown rather than on every frame.
+#[derive(Debug, Default)]
+pub struct FlagCache {
+    /// Offset of the key the cached values belong to.
+    key: Option<usize>,
+    /// Flags by the offset of the value.
+    flags: HashMap<usize, String>,
+}
+
+impl FlagCache {
+    /// The flags of the value at `value` with `content`, one of the values of
+    /// the key at `key`. Flags of the previous key are dropped when it changes.
+    pub fn flags(&mut self, key: usize, value: usize, content: &CellValue) -> String {
+        if self.key != Some(key) {
+            self.key = Some(key);
+            self.flags.clear();
+        }
+        self.flags
+            .entry(value)
+            .or_insert_with(|| sniff(content).flags())
+            .clone()
+    }
+}
+
+/// Looks for encoded, compressed or executable content and for URLs and IP
+/// addresses in value data, peeling off one layer after the other.
+pub fn sniff(content: &CellValue) -> Sniffed {
+    let data = match content {
+        CellValue::String(text) => text.as_bytes().to_vec(),
+        CellValue::MultiString(texts) => texts.join("\n").into_bytes(),
+        _ => raw_data(content),
+    };
+    let mut sniffed = Sniffed::default();
+    let mut data = data[..data.len().min(MAX_INPUT)].to_vec();
+    find_addresses(&data, &mut sniffed);
+    while sniffed.layers.len() < MAX_LAYERS {
+        let Some(layer) = peel(&data) else {
+            break;
+        };
+        find_addresses(&layer.data, &mut sniffed);
+        data = layer.data.clone();
+        let executable = layer.kind == ContentKind::Executable;
+        sniffed.layers.push(layer);
+        if executable {
+            break;
+        }
+    }
+    sniffed
+}
+
+/// Decodes the outermost layer of `data`, if it has one.
+fn peel(data: &[u8]) -> Option<Layer> {
+    let layer = |kind, data| Some(Layer { kind, data });
+    if let Some(script) = encoded_command(data) {
+        return layer(ContentKind::PowerShell, script);
+    }
+    if let Some(decoded) = base64(data) {
+        return layer(ContentKind::Base64, decoded);
+    }
+    if data.starts_with(&[0x1F, 0x8B]) {
+        if let Some(decompressed) = decompress(GzDecoder::new(data)) {
+            return layer(ContentKind::Gzip, decompressed);
+        }
+    }
+    if is_zlib_header(data) {
+        if let Some(decompressed) = decompress(ZlibDecoder::new(data)) {
+            return layer(ContentKind::Zlib, decompressed);
+        }
+    }
+    if let Some(offset) = executable_offset(data) {
+        return layer(ContentKind::Executable, data[offset..].to_vec());
+    }
+    if is_utf16_text(data) {
+        return layer(ContentKind::Utf16, utf16_string(data).into_bytes());
+    }
+    None
+}
+
+/// The script of a `powershell -EncodedCommand` line, as UTF-8.
+fn encoded_command(data: &[u8]) -> Option<Vec<u8>> {
+    let encoded = ENCODED_COMMAND.captures(data)?.get(1)?.as_bytes();
+    let decoded = STANDARD.decode(encoded).ok()?;
+    Some(utf16_string(&decoded).into_bytes())
+}
+
+/// Decodes data that is nothing but base64 text. Hex strings, which use a
+/// subset of the alphabet, are left alone.
+fn base64(data: &[u8]) -> Option<Vec<u8>> {
+    let text: Vec<u8> = data
+        .iter()
+        .copied()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    let text = text.strip_suffix(&[0]).unwrap_or(&text);
+    // Long words and identifiers pass for base64 too, real blobs mix cases and digits.
+    let has = |class: fn(&u8) -> bool| text.iter().any(class);
+    let mixed = has(|b| b"+/=".contains(b))
+        || (has(u8::is_ascii_uppercase) && has(u8::is_ascii_lowercase) && has(u8::is_ascii_digit));
+    if text.len() < MIN_BASE64_LENGTH
+        || !text.len().is_multiple_of(4)
+        || text.iter().all(u8::is_ascii_hexdigit)
+        || !mixed
+    {
+        return None;
+    }
+    STANDARD.decode(text).ok()
+}
+
+fn is_zlib_header(data: &[u8]) -> bool {
+    match data {
+        [first, second, ..] => {
+            first & 0x0F == 8 && (*first as u16 * 256 + *second as u16).is_multiple_of(31)
+        }
+        _ => false,
+    }
+}
+
+fn decompress(decoder: impl Read) -> Option<Vec<u8>> {
+    let mut decompressed = vec![];
+    decoder
+        .take(MAX_DECOMPRESSED)
+        .read_to_end(&mut decompressed)
+        .ok()?;
+    (!decompressed.is_empty()).then_some(decompressed)
+}
+
+/// Offset of the first MZ header whose `e_lfanew` points at a PE signature.
+fn executable_offset(data: &[u8]) -> Option<usize> {
+    (0..data.len().saturating_sub(0x40)).find(|offset| {
+        let header = &data[*offset..];
+        header.starts_with(b"MZ")
+            && read_u32(header, 0x3C).is_some_and(|pe_offset| {
+                header
+                    .get(pe_offset as usize..)
+                    .is_some_and(|pe| pe.starts_with(b"PE\0\0"))
+            })
+    })
+}
+
+/// Whether `data` reads as UTF-16LE text with few non-printable characters.
+fn is_utf16_text(data: &[u8]) -> bool {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+    let printable = units
+        .iter()
+        .filter(|unit| matches!(unit, 0x09 | 0x0A | 0x0D | 0x20..=0x7E))
+        .count();
+    units.len() >= MIN_STRING_LENGTH && printable * 10 >= units.len() * 9
+}
+
+/// Adds the URLs and IP addresses in the printable runs of `data` to `sniffed`.
+fn find_addresses(data: &[u8], sniffed: &mut Sniffed) {
+    for text in strings(data) {
+        for (regex, found) in [
+            (&*URL, &mut sniffed.urls),
+            (&*IP_ADDRESS, &mut sniffed.addresses),
+        ] {
+            for hit in regex.find_iter(&text) {
+                let hit = String::from_utf8_lossy(hit.as_bytes()).to_string();
+                if found.len() < MAX_FINDINGS && !found.contains(&hit) {
+                    found.push(hit);
+                }
+            }
+        }
+    }
+}
+
+/// Runs of printable ASCII, read as single bytes and as UTF-16LE.
+fn strings(data: &[u8]) -> Vec<Vec<u8>> {
+    let printable = |b: &u8| b.is_ascii_graphic() || *b == b' ';
+    let mut runs: Vec<Vec<u8>> = data
+        .split(|b| !printable(b))
+        .filter(|run| run.len() >= MIN_STRING_LENGTH)
+        .map(<[u8]>::to_vec)
+        .collect();
+    for start in [0, 1] {
+        let units: Vec<u8> = data
+            .get(start..)
+            .unwrap_or_default()
+            .chunks_exact(2)
+            .map(|pair| if pair[1] == 0 { pair[0] } else { 0 })
+            .collect();
+        runs.extend(
+            units
+                .split(|b| !printable(b))
+                .filter(|run| run.len() >= MIN_STRING_LENGTH)
+                .map(<[u8]>::to_vec),
+        );
+    }
+    runs
+}
@@ -0,0 +1,275 @@
+use notatin::cell_value::CellValue;
+
+use crate::decoders::shell_item::{self, id_list};
+use crate::decoders::{binary, format_filetime, path_matches, Decoder, Field};
+use crate::hive::utf16_string;
+use crate::hive::{read_u32, read_u64};
+
+/// Known folders UserAssist entries start with instead of a drive letter.
+const USERASSIST_FOLDERS: &[(&str, &str)] = &[
+    (
+        "{0139D44E-6AFE-49F2-8690-3DAFCAE6FFB8}",
+        "%ProgramData%\\Microsoft\\Windows\\Start Menu\\Programs",
+    ),
+    (
+        "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}",
+        "%SystemRoot%\\System32",
+    ),
+    ("{6D809377-6AF0-444B-8957-A3773F02200E}", "%ProgramFiles%"),
+    (
+        "{7C5A40EF-A0FB-4BFC-874A-C0F2E0B9FA8E}",
+        "%ProgramFiles(x86)%",
+    ),
+    (
+        "{9E3995AB-1F9C-4F13-B827-48B24B6C7174}",
+        "%AppData%\\Microsoft\\Internet Explorer\\Quick Launch\\User Pinned",
+    ),
+    (
+        "{A77F5D77-2E2B-44C3-A6A2-ABA601054A51}",
+        "%AppData%\\Microsoft\\Windows\\Start Menu\\Programs",
+    ),
+    (
+        "{D65231B0-B2F1-4857-A4CE-A8E7C6EA7D27}",
+        "%SystemRoot%\\SysWOW64",
+    ),
+    ("{F38BF404-1D43-42F2-9305-67DE0B28FC23}", "%SystemRoot%"),
+];
+
+/// Programs and shortcuts started from Explorer, with ROT13 encoded names and run counters.
+pub struct UserAssist;
+
+impl Decoder for UserAssist {
+    fn name(&self) -> &'static str {
+        "UserAssist"
+    }
+
+    fn matches(&self, key_path: &str, _value_name: &str) -> bool {
+        path_matches(
+            key_path,
+            "**\\Microsoft\\Windows\\CurrentVersion\\Explorer\\UserAssist\\*\\Count",
+        )
+    }
+
+    fn decode(&self, value_name: &str, data: &CellValue) -> Option<Vec<Field>> {
+        let data = binary(data)?;
+        let mut name = rot13(value_name);
+        if let Some((rest, folder)) = USERASSIST_FOLDERS.iter().find_map(|(guid, folder)| {
+            let prefix = name.get(..guid.len())?;
+            prefix
+                .eq_ignore_ascii_case(guid)
+                .then(|| (&name[guid.len()..], folder))
+        }) {
+            name = format!("{}{}", folder, rest);
+        }
+
+        let mut fields = vec![Field::new("Name", name)];
+        match data.len() {
+            // Windows 7 and later.
+            72 => fields.extend([
+                Field::new("Run count", read_u32(data, 4)?.to_string()),
+                Field::new("Focus count", read_u32(data, 8)?.to_string()),
+                Field::new("Focus time", duration(read_u32(data, 12)?)),
+                Field::new("Last run", format_filetime(read_u64(data, 60)?)),
+            ]),
+            // Windows XP counts from 5.
+            16 => fields.extend([
+                Field::new(
+                    "Run count",
+                    read_u32(data, 4)?.saturating_sub(5).to_string(),
+                ),
+                Field::new("Last run", format_filetime(read_u64(data, 8)?)),
+            ]),
+            _ => return None,
+        }
+        Some(fields)
+    }
+}
+
+/// Order of the entries in a most recently used list, most recent first.
+pub struct MruListEx;
+
+impl Decoder for MruListEx {
+    fn name(&self) -> &'static str {
+        "MRUListEx"
+    }
+
+    fn matches(&self, _key_path: &str, value_name: &str) -> bool {
+        value_name.eq_ignore_ascii_case("MRUListEx")
+    }
+
+    fn decode(&self, _value_name: &str, data: &CellValue) -> Option<Vec<Field>> {
+        let data = binary(data)?;
+        let order: Vec<String> = data
+            .chunks_exact(4)
+            .map(|entry| u32::from_le_bytes(entry.try_into().unwrap()))
+            .take_while(|entry| *entry != u32::MAX)
+            .map(|entry| entry.to_string())
+            .collect();
+        Some(vec![
+            Field::new("Entries", order.len().to_string()),
+            Field::new("Most recent first", order.join(", ")),
+        ])
+    }
+}
+
+/// Recently opened documents, each a file name followed by the ID list of its shortcut.
+pub struct RecentDocs;
+
+impl Decoder for RecentDocs {
+    fn name(&self) -> &'static str {
+        "RecentDocs"
+    }
+
+    fn matches(&self, key_path: &str, value_name: &str) -> bool {
+        is_number(value_name)
+            && (path_matches(key_path, "**\\Explorer\\RecentDocs")
+                || path_matches(key_path, "**\\Explorer\\RecentDocs\\*"))
+    }
+
+    fn decode(&self, _value_name: &str, data: &CellValue) -> Option<Vec<Field>> {
+        let data = binary(data)?;
+        let name = utf16_string(data);
+        let mut fields = vec![Field::new("File name", name.clone())];
+
+        let items_offset = (name.encode_utf16().count() + 1) * 2;
+        if let Some(item) = data
+            .get(items_offset..)
+            .and_then(|items| id_list(items).first().copied())
+        {
+            fields.push(Field::new("Shortcut", shell_item::name(item)));
+        }
+        Some(fields)
+    }
+}
+
+/// Paths typed into the Explorer address bar, `url1` being the most recent.
+pub struct TypedPaths;
+
+impl Decoder for TypedPaths {
+    fn name(&self) -> &'static str {
+        "TypedPaths"
+    }
+
+    fn matches(&self, key_path: &str, value_name: &str) -> bool {
+        path_matches(key_path, "**\\Explorer\\TypedPaths")
+            && position(value_name).is_some_and(|position| !position.is_empty())
+    }
+
+    fn decode(&self, value_name: &str, data: &CellValue) -> Option<Vec<Field>> {
+        let CellValue::String(path) = data else {
+            return None;
+        };
+        Some(vec![
+            Field::new("Position", position(value_name)?.to_string()),
+            Field::new("Path", path.clone()),
+        ])
+    }
+}
+
+/// Folders opened in Explorer. Each numbered value holds the shell item of one
+/// folder, whose subfolders are listed in the subkey of the same number.
+pub struct ShellBags;
+
+impl Decoder for ShellBags {
+    fn name(&self) -> &'static str {
+        "ShellBags"
+    }
+
+    fn matches(&self, key_path: &str, value_name: &str) -> bool {
+        is_number(value_name)
+            && (path_matches(key_path, "**\\BagMRU") || path_matches(key_path, "**\\BagMRU\\**"))
+    }
+
+    fn decode(&self, _value_name: &str, data: &CellValue) -> Option<Vec<Field>> {
+        let data = binary(data)?;
+        let item = *id_list(data).first()?;
+        Some(shell_item::describe(item))
+    }
+}
+
+/// What follows `url` in the name of a TypedPaths value.
+fn position(value_name: &str) -> Option<&str> {
+    let prefix = value_name.get(..3)?;
+    prefix.eq_ignore_ascii_case("url").then(|| &value_name[3..])
+}
+
+fn is_number(value_name: &str) -> bool {
+    !value_name.is_empty() && value_name.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn rot13(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'a'..='m' | 'A'..='M' => (c as u8 + 13) as char,
+            'n'..='z' | 'N'..='Z' => (c as u8 - 13) as char,
+            _ => c,
+        })
+        .collect()
+}
+
+fn duration(milliseconds: u32) -> String {
+    let seconds = milliseconds / 1000;
+    format!(
+        "{}:{:02}:{:02}.{:03}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        milliseconds % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_assist(name: &str, data: Vec<u8>) -> Option<Vec<Field>> {
+        UserAssist.decode(&rot13(name), &CellValue::Binary(data))
+    }
+
+    #[test]
+    fn decodes_user_assist_entries() {
+        let mut data = vec![0; 72];
+        data[4] = 3;
+        let fields = user_assist(
+            "{6D809377-6AF0-444B-8957-A3773F02200E}\\Tool\\tool.exe",
+            data,
+        )
+        .unwrap();
+        assert_eq!(fields[0].value, "%ProgramFiles%\\Tool\\tool.exe");
+        assert_eq!(fields[1], Field::new("Run count", "3"));
+    }
+
+    #[test]
+    fn keeps_user_assist_names_with_non_ascii_characters() {
+        // The known folder GUIDs are 38 bytes long, which ends inside the "é" here.
+        let name = "C:\\Users\\Bob\\Programs\\éditeur.exe";
+        assert!(!name.is_char_boundary(38));
+        let fields = user_assist(name, vec![0; 72]).unwrap();
+        assert_eq!(fields[0].value, name);
+    }
+
+    #[test]
+    fn rejects_truncated_user_assist_data() {
+        assert_eq!(user_assist("C:\\tool.exe", vec![0; 40]), None);
+        assert_eq!(user_assist("C:\\tool.exe", vec![]), None);
+    }
+
+    #[test]
+    fn matches_typed_paths_by_name() {
+        let key = "Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\TypedPaths";
+        assert!(TypedPaths.matches(key, "url1"));
+        assert!(TypedPaths.matches(key, "URL12"));
+        assert!(!TypedPaths.matches(key, "url"));
+        assert!(!TypedPaths.matches(key, "ür1"));
+        assert!(!TypedPaths.matches(key, "uré"));
+
+        let fields = TypedPaths
+            .decode("url2", &CellValue::String("C:\\Temp".to_string()))
+            .unwrap();
+        assert_eq!(fields[0], Field::new("Position", "2"));
+        assert_eq!(
+            TypedPaths.decode("ü", &CellValue::String(String::new())),
+            None
+        );
+    }
+}
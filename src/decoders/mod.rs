@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use notatin::cell_value::CellValue;
+
+pub mod bam;
+pub mod content;
+pub mod explorer;
+pub mod resources;
+pub mod sam;
+pub mod shell_item;
+pub mod shimcache;
+pub mod timestamp;
+
+/// One labelled piece of information a decoder pulled out of a value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub value: String,
+}
+
+impl Field {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Field {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+/// Interprets the data of values with a well-known layout.
+pub trait Decoder: Sync {
+    /// Name shown above the decoded fields.
+    fn name(&self) -> &'static str;
+
+    /// Whether values called `value_name` in the key at `key_path` (below the root key)
+    /// have the layout this decoder understands.
+    fn matches(&self, key_path: &str, value_name: &str) -> bool;
+
+    /// Decodes the value's data, or returns `None` if it does not have the expected layout.
+    fn decode(&self, value_name: &str, data: &CellValue) -> Option<Vec<Field>>;
+}
+
+/// Every known decoder, tried in order.
+static DECODERS: &[&dyn Decoder] = &[
+    &explorer::UserAssist,
+    &explorer::MruListEx,
+    &explorer::RecentDocs,
+    &explorer::TypedPaths,
+    &explorer::ShellBags,
+    &shimcache::ShimCache,
+    &bam::Bam,
+    &sam::SamUser,
+];
+
+/// A value decoded by one of the registered decoders.
+#[derive(Debug, Clone)]
+pub struct Decoded {
+    pub decoder: &'static str,
+    pub fields: Vec<Field>,
+}
+
+/// Decodes the value called `value_name` in the key at `key_path` with the first
+/// decoder that recognises it.
+pub fn decode(key_path: &str, value_name: &str, data: &CellValue) -> Option<Decoded> {
+    DECODERS
+        .iter()
+        .filter(|decoder| decoder.matches(key_path, value_name))
+        .find_map(|decoder| {
+            Some(Decoded {
+                decoder: decoder.name(),
+                fields: decoder.decode(value_name, data)?,
+            })
+        })
+}
+
+/// Matches a key path below the root key against a pattern of backslash-separated
+/// components, ignoring case. `*` stands for any one component and `**` for any
+/// number of them, including none.
+pub fn path_matches(path: &str, pattern: &str) -> bool {
+    fn matches(path: &[&str], pattern: &[&str]) -> bool {
+        match (pattern.first(), path.first()) {
+            (None, _) => path.is_empty(),
+            (Some(&"**"), _) => {
+                matches(path, &pattern[1..]) || (!path.is_empty() && matches(&path[1..], pattern))
+            }
+            (Some(_), None) => false,
+            (Some(&"*"), Some(_)) => matches(&path[1..], &pattern[1..]),
+            (Some(expected), Some(component)) => {
+                expected.eq_ignore_ascii_case(component) && matches(&path[1..], &pattern[1..])
+            }
+        }
+    }
+    let path: Vec<&str> = path.split('\\').filter(|c| !c.is_empty()).collect();
+    let pattern: Vec<&str> = pattern.split('\\').collect();
+    matches(&path, &pattern)
+}
+
+/// Converts a FILETIME, 100 nanosecond intervals since 1601-01-01 UTC, to a time.
+/// Returns `None` for 0, which stands for "never", and for times out of range.
+pub fn filetime(filetime: u64) -> Option<DateTime<Utc>> {
+    /// Seconds between 1601-01-01 and 1970-01-01.
+    const EPOCH_DIFFERENCE: i64 = 11_644_473_600;
+    if filetime == 0 || filetime > i64::MAX as u64 {
+        return None;
+    }
+    let seconds = (filetime / 10_000_000) as i64 - EPOCH_DIFFERENCE;
+    let nanoseconds = (filetime % 10_000_000) as u32 * 100;
+    DateTime::from_timestamp(seconds, nanoseconds)
+}
+
+/// A FILETIME as shown in decoded fields.
+pub fn format_filetime(value: u64) -> String {
+    match filetime(value) {
+        Some(time) => time.format("%Y-%m-%d %H:%M:%S%.3f UTC").to_string(),
+        None if value == 0 => "never".to_string(),
+        None => format!("{:#018x} (never)", value),
+    }
+}
+
+/// The bytes of binary value data.
+fn binary(data: &CellValue) -> Option<&[u8]> {
+    match data {
+        CellValue::Binary(bytes) => Some(bytes),
+        _ => None,
+    }
+}
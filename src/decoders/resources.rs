@@ -0,0 +1,282 @@
+use notatin::cell_key_value::CellKeyValueDataTypes;
+
+use crate::hive::{read_u16, read_u32, read_u64};
+
+/// Size of a `CM_PARTIAL_RESOURCE_DESCRIPTOR` written by 64 and 32 bit Windows.
+/// The interrupt affinity is pointer sized, which makes the union wider on x64.
+const PARTIAL_DESCRIPTOR_SIZES: [usize; 2] = [20, 16];
+/// Size of the `CM_FULL_RESOURCE_DESCRIPTOR` fields before its partial descriptors.
+const FULL_DESCRIPTOR_HEADER_SIZE: usize = 16;
+/// Size of the `IO_RESOURCE_REQUIREMENTS_LIST` fields before its alternative lists.
+const REQUIREMENTS_HEADER_SIZE: usize = 32;
+/// Size of the `IO_RESOURCE_LIST` fields before its descriptors.
+const IO_LIST_HEADER_SIZE: usize = 8;
+const IO_DESCRIPTOR_SIZE: usize = 32;
+/// `CmResourceTypeDeviceSpecific`, whose data follows the descriptor.
+const DEVICE_SPECIFIC: u8 = 5;
+
+/// One line of a decoded resource structure with the lines nested below it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub label: String,
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    fn new(label: impl Into<String>) -> Self {
+        Node {
+            label: label.into(),
+            children: vec![],
+        }
+    }
+
+    fn with(label: impl Into<String>, children: Vec<Node>) -> Self {
+        Node {
+            label: label.into(),
+            children,
+        }
+    }
+
+    /// The node and its descendants as lines indented by depth.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![self.label.clone()];
+        for child in &self.children {
+            lines.extend(child.lines().into_iter().map(|line| format!("  {}", line)));
+        }
+        lines
+    }
+}
+
+/// Decodes the data of the resource data types, `None` for other types or data
+/// that does not have the expected layout.
+pub fn decode(data_type: CellKeyValueDataTypes, data: &[u8]) -> Option<Vec<Node>> {
+    match data_type {
+        CellKeyValueDataTypes::REG_RESOURCE_LIST => resource_list(data),
+        CellKeyValueDataTypes::REG_FULL_RESOURCE_DESCRIPTOR => full_resource_descriptor(data),
+        CellKeyValueDataTypes::REG_RESOURCE_REQUIREMENTS_LIST => requirements_list(data),
+        _ => None,
+    }
+}
+
+/// Decodes a `REG_RESOURCE_LIST`: a count followed by that many full resource descriptors.
+pub fn resource_list(data: &[u8]) -> Option<Vec<Node>> {
+    let count = read_u32(data, 0)? as usize;
+    PARTIAL_DESCRIPTOR_SIZES
+        .iter()
+        .find_map(|size| exact(full_descriptors(&data[4..], count, *size), data.len() - 4))
+}
+
+/// Decodes a `REG_FULL_RESOURCE_DESCRIPTOR`.
+pub fn full_resource_descriptor(data: &[u8]) -> Option<Vec<Node>> {
+    PARTIAL_DESCRIPTOR_SIZES
+        .iter()
+        .find_map(|size| exact(full_descriptors(data, 1, *size), data.len()))
+}
+
+/// Decodes a `REG_RESOURCE_REQUIREMENTS_LIST`: alternative sets of resources a device can work with.
+pub fn requirements_list(data: &[u8]) -> Option<Vec<Node>> {
+    let interface = interface_type(read_u32(data, 4)?);
+    let bus = read_u32(data, 8)?;
+    let slot = read_u32(data, 12)?;
+    let alternatives = read_u32(data, 28)? as usize;
+
+    let mut offset = REQUIREMENTS_HEADER_SIZE;
+    let mut lists = vec![];
+    for index in 0..alternatives {
+        let count = read_u32(data, offset + 4)? as usize;
+        offset += IO_LIST_HEADER_SIZE;
+        let descriptors = (0..count)
+            .map(|descriptor| io_descriptor(data.get(offset + descriptor * IO_DESCRIPTOR_SIZE..)?))
+            .collect::<Option<Vec<Node>>>()?;
+        offset += count * IO_DESCRIPTOR_SIZE;
+        lists.push(Node::with(
+            format!("Alternative {}", index + 1),
+            descriptors,
+        ));
+    }
+    Some(vec![Node::with(
+        format!("{} bus {}, slot {}", interface, bus, slot),
+        lists,
+    )])
+}
+
+/// Keeps a parse result only if it used up exactly `len` bytes.
+fn exact(parsed: Option<(Vec<Node>, usize)>, len: usize) -> Option<Vec<Node>> {
+    parsed
+        .filter(|(_, consumed)| *consumed == len)
+        .map(|(nodes, _)| nodes)
+}
+
+/// Parses `count` full resource descriptors with partial descriptors of
+/// `partial_size` bytes, returning them with the number of bytes read.
+fn full_descriptors(data: &[u8], count: usize, partial_size: usize) -> Option<(Vec<Node>, usize)> {
+    let mut offset = 0;
+    let mut nodes = vec![];
+    for _ in 0..count {
+        let interface = interface_type(read_u32(data, offset)?);
+        let bus = read_u32(data, offset + 4)?;
+        let version = read_u16(data, offset + 8)?;
+        let revision = read_u16(data, offset + 10)?;
+        let partial_count = read_u32(data, offset + 12)? as usize;
+        offset += FULL_DESCRIPTOR_HEADER_SIZE;
+
+        let mut partials = vec![];
+        for _ in 0..partial_count {
+            let descriptor = data.get(offset..offset + partial_size)?;
+            partials.push(partial_descriptor(descriptor, partial_size));
+            offset += partial_size;
+            if descriptor[0] == DEVICE_SPECIFIC {
+                offset += read_u32(descriptor, 4)? as usize;
+            }
+        }
+        nodes.push(Node::with(
+            format!(
+                "{} bus {} (version {}.{})",
+                interface, bus, version, revision
+            ),
+            partials,
+        ));
+    }
+    Some((nodes, offset))
+}
+
+/// Describes a `CM_PARTIAL_RESOURCE_DESCRIPTOR`, whose union starts at offset 4.
+fn partial_descriptor(descriptor: &[u8], size: usize) -> Node {
+    let resource = descriptor[0];
+    let share = descriptor[1];
+    let flags = read_u16(descriptor, 2).unwrap_or_default();
+    let u = &descriptor[4..];
+    let field = |offset| read_u32(u, offset).unwrap_or_default();
+    let label = match resource {
+        0 => "Null".to_string(),
+        1 | 3 => {
+            let start = read_u64(u, 0).unwrap_or_default();
+            let length = field(8) as u64;
+            format!(
+                "{} {:#x}-{:#x} ({} bytes)",
+                if resource == 1 { "Port" } else { "Memory" },
+                start,
+                start.saturating_add(length).saturating_sub(1),
+                length
+            )
+        }
+        2 => {
+            let affinity = match size {
+                20 => read_u64(u, 8).unwrap_or_default(),
+                _ => field(8) as u64,
+            };
+            format!(
+                "Interrupt level {}, vector {}, affinity {:#x}",
+                field(0),
+                field(4),
+                affinity
+            )
+        }
+        4 => format!("DMA channel {}, port {}", field(0), field(4)),
+        DEVICE_SPECIFIC => format!("Device specific data, {} bytes", field(0)),
+        6 => format!(
+            "Bus numbers {}-{}",
+            field(0),
+            field(0).saturating_add(field(4)).saturating_sub(1)
+        ),
+        0x81 => "Configuration data".to_string(),
+        0x82 => format!(
+            "Device private data {:#x} {:#x} {:#x}",
+            field(0),
+            field(4),
+            field(8)
+        ),
+        other => format!("Resource type {:#x}", other),
+    };
+    Node::with(
+        label,
+        vec![
+            Node::new(format!("Share: {}", share_disposition(share))),
+            Node::new(format!("Flags: {:#06x}", flags)),
+        ],
+    )
+}
+
+/// Describes an `IO_RESOURCE_DESCRIPTOR`, whose union starts at offset 8.
+fn io_descriptor(descriptor: &[u8]) -> Option<Node> {
+    let descriptor = descriptor.get(..IO_DESCRIPTOR_SIZE)?;
+    let option = descriptor[0];
+    let resource = descriptor[1];
+    let share = descriptor[2];
+    let flags = read_u16(descriptor, 4)?;
+    let u = &descriptor[8..];
+    let label = match resource {
+        1 | 3 => format!(
+            "{} of {} bytes aligned to {:#x} within {:#x}-{:#x}",
+            if resource == 1 { "Port" } else { "Memory" },
+            read_u32(u, 0)?,
+            read_u32(u, 4)?,
+            read_u64(u, 8)?,
+            read_u64(u, 16)?
+        ),
+        2 => format!("Interrupt vector {}-{}", read_u32(u, 0)?, read_u32(u, 4)?),
+        4 => format!("DMA channel {}-{}", read_u32(u, 0)?, read_u32(u, 4)?),
+        6 => format!(
+            "{} bus numbers within {}-{}",
+            read_u32(u, 0)?,
+            read_u32(u, 4)?,
+            read_u32(u, 8)?
+        ),
+        0x81 => "Configuration data".to_string(),
+        other => format!("Resource type {:#x}", other),
+    };
+    let option = match option {
+        0 => "required",
+        1 => "preferred",
+        2 => "default",
+        8 => "alternative",
+        _ => "other",
+    };
+    Some(Node::with(
+        label,
+        vec![
+            Node::new(format!("Option: {}", option)),
+            Node::new(format!("Share: {}", share_disposition(share))),
+            Node::new(format!("Flags: {:#06x}", flags)),
+        ],
+    ))
+}
+
+/// Name of an `INTERFACE_TYPE`.
+fn interface_type(value: u32) -> String {
+    const NAMES: [&str; 18] = [
+        "Internal",
+        "ISA",
+        "EISA",
+        "MicroChannel",
+        "TurboChannel",
+        "PCI",
+        "VME",
+        "NuBus",
+        "PCMCIA",
+        "CBus",
+        "MPI",
+        "MPSA",
+        "Processor internal",
+        "Internal power",
+        "PNP ISA",
+        "PNP",
+        "VMCS",
+        "ACPI",
+    ];
+    match NAMES.get(value as usize) {
+        Some(name) => name.to_string(),
+        None if value == u32::MAX => "Undefined".to_string(),
+        None => format!("Interface {}", value),
+    }
+}
+
+fn share_disposition(value: u8) -> &'static str {
+    match value {
+        0 => "undetermined",
+        1 => "device exclusive",
+        2 => "driver exclusive",
+        3 => "shared",
+        _ => "unknown",
+    }
+}
@@ -0,0 +1,79 @@
+use notatin::cell_value::CellValue;
+
+use crate::decoders::{binary, format_filetime, path_matches, Decoder, Field};
+use crate::hive::utf16_string;
+use crate::hive::{read_u16, read_u32, read_u64};
+
+/// Offset the `V` value's string offsets are relative to.
+const V_DATA_OFFSET: usize = 0xCC;
+
+/// Account control flags of the `F` value.
+const ACCOUNT_FLAGS: &[(u16, &str)] = &[
+    (0x0001, "disabled"),
+    (0x0002, "home directory required"),
+    (0x0004, "password not required"),
+    (0x0008, "temporary duplicate account"),
+    (0x0010, "normal account"),
+    (0x0020, "MNS logon account"),
+    (0x0040, "interdomain trust account"),
+    (0x0080, "workstation trust account"),
+    (0x0100, "server trust account"),
+    (0x0200, "password does not expire"),
+    (0x0400, "locked out"),
+];
+
+/// The `F` and `V` values of a local user account in the SAM hive.
+pub struct SamUser;
+
+impl Decoder for SamUser {
+    fn name(&self) -> &'static str {
+        "SAM user"
+    }
+
+    fn matches(&self, key_path: &str, value_name: &str) -> bool {
+        (value_name == "F" || value_name == "V")
+            && path_matches(key_path, "SAM\\Domains\\Account\\Users\\*")
+    }
+
+    fn decode(&self, value_name: &str, data: &CellValue) -> Option<Vec<Field>> {
+        let data = binary(data)?;
+        match value_name {
+            "F" => fixed(data),
+            _ => variable(data),
+        }
+    }
+}
+
+/// Times and counters of the account.
+fn fixed(data: &[u8]) -> Option<Vec<Field>> {
+    let flags = read_u16(data, 0x38)?;
+    let flag_names: Vec<&str> = ACCOUNT_FLAGS
+        .iter()
+        .filter(|(flag, _)| flags & flag != 0)
+        .map(|(_, name)| *name)
+        .collect();
+    Some(vec![
+        Field::new("RID", read_u32(data, 0x30)?.to_string()),
+        Field::new("Last logon", format_filetime(read_u64(data, 0x08)?)),
+        Field::new("Password last set", format_filetime(read_u64(data, 0x18)?)),
+        Field::new("Account expires", format_filetime(read_u64(data, 0x20)?)),
+        Field::new("Last failed logon", format_filetime(read_u64(data, 0x28)?)),
+        Field::new("Flags", format!("{:#06x} {}", flags, flag_names.join(", "))),
+        Field::new("Failed logons", read_u16(data, 0x40)?.to_string()),
+        Field::new("Logons", read_u16(data, 0x42)?.to_string()),
+    ])
+}
+
+/// Names of the account.
+fn variable(data: &[u8]) -> Option<Vec<Field>> {
+    let string = |entry: usize| {
+        let offset = V_DATA_OFFSET + read_u32(data, entry)? as usize;
+        let length = read_u32(data, entry + 4)? as usize;
+        data.get(offset..offset + length).map(utf16_string)
+    };
+    Some(vec![
+        Field::new("User name", string(0x0C)?),
+        Field::new("Full name", string(0x18).unwrap_or_default()),
+        Field::new("Comment", string(0x24).unwrap_or_default()),
+    ])
+}
@@ -0,0 +1,198 @@
+use chrono::NaiveDate;
+
+use crate::decoders::Field;
+use crate::hive::utf16_string;
+use crate::hive::{read_u16, read_u32};
+
+/// Signature of the extension block carrying a file entry's long name and times.
+const BEEF0004: u32 = 0xBEEF_0004;
+
+/// Names of the shell folders root folder items most often point at.
+const KNOWN_FOLDERS: &[(&str, &str)] = &[
+    ("20D04FE0-3AEA-1069-A2D8-08002B30309D", "This PC"),
+    ("450D8FBA-AD25-11D0-98A8-0800361B1103", "My Documents"),
+    ("208D2C60-3AEA-1069-A2D7-08002B30309D", "My Network Places"),
+    ("F02C1A0D-BE21-4350-88B0-7367FC96EF3C", "Network"),
+    ("645FF040-5081-101B-9F08-00AA002F954E", "Recycle Bin"),
+    ("21EC2020-3AEA-1069-A2DD-08002B30309D", "Control Panel"),
+    ("26EE0668-A00A-44D7-9371-BEB064C98683", "Control Panel"),
+    ("59031A47-3F72-44A7-89C5-5595FE6B30EE", "User Files"),
+    ("031E4825-7B94-4DC3-B131-E946B44C8DD5", "Libraries"),
+    ("679F85CB-0220-4080-B29B-5540CC05AAB6", "Quick Access"),
+    ("F874310E-B6B7-47DC-BC84-B9E6B38F5903", "Home"),
+    ("B4BFCC3A-DB2C-424C-B029-7FE99A87C641", "Desktop"),
+    ("374DE290-123F-4565-9164-39C4925E467B", "Downloads"),
+    ("A8CDFF1C-4878-43BE-B5FD-F8091C1C60D0", "Documents"),
+    ("3DFDF296-DBEC-4FB4-81D1-6A3438BCF4DE", "Music"),
+    ("24AD3AD4-A569-4530-98E1-AB02F9417AA8", "Pictures"),
+    ("F86FA3AB-70D2-4FC7-9C99-FCBF05467F3A", "Videos"),
+];
+
+/// Splits an ID list, as Explorer stores them for ShellBags and most recently
+/// used lists, into its shell items. Stops at the terminating empty item.
+pub fn id_list(data: &[u8]) -> Vec<&[u8]> {
+    let mut items = vec![];
+    let mut offset = 0;
+    while let Some(size) = read_u16(data, offset) {
+        let size = size as usize;
+        if size < 3 || offset + size > data.len() {
+            break;
+        }
+        items.push(&data[offset..offset + size]);
+        offset += size;
+    }
+    items
+}
+
+/// Describes one shell item. The item includes its own size field.
+pub fn describe(item: &[u8]) -> Vec<Field> {
+    let Some(&class) = item.get(2) else {
+        return vec![];
+    };
+    match class {
+        0x1F => root_folder(item),
+        0x20..=0x2F => vec![
+            Field::new("Type", "Volume"),
+            Field::new("Name", ansi_string(item.get(3..).unwrap_or_default())),
+        ],
+        0x30..=0x3F => file_entry(item, class),
+        0x61 => vec![Field::new("Type", "URI")],
+        0x71 => vec![Field::new("Type", "Control Panel item")],
+        _ => vec![Field::new("Type", format!("unknown ({:#04x})", class))],
+    }
+}
+
+/// The name shown for an item in paths built from ID lists.
+pub fn name(item: &[u8]) -> String {
+    let fields = describe(item);
+    ["Long name", "Name"]
+        .iter()
+        .find_map(|name| fields.iter().find(|field| field.name == *name))
+        .map_or_else(|| "?".to_string(), |field| field.value.clone())
+}
+
+fn root_folder(item: &[u8]) -> Vec<Field> {
+    let Some(guid) = item.get(4..20).map(format_guid) else {
+        return vec![Field::new("Type", "Root folder")];
+    };
+    let name = KNOWN_FOLDERS
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(&guid))
+        .map_or_else(|| format!("{{{}}}", guid), |(_, name)| name.to_string());
+    vec![Field::new("Type", "Root folder"), Field::new("Name", name)]
+}
+
+fn file_entry(item: &[u8], class: u8) -> Vec<Field> {
+    let kind = if class & 0x01 != 0 { "Folder" } else { "File" };
+    let mut fields = vec![Field::new("Type", kind)];
+    if let Some(size) = read_u32(item, 4) {
+        if class & 0x01 == 0 {
+            fields.push(Field::new("Size", size.to_string()));
+        }
+    }
+    if let Some(modified) = read_u32(item, 8).and_then(dos_time) {
+        fields.push(Field::new("Modified", modified));
+    }
+
+    // The short name is ANSI, or UTF-16 for items flagged as Unicode.
+    let unicode = class & 0x04 != 0;
+    let rest = item.get(14..).unwrap_or_default();
+    let (short_name, name_size) = if unicode {
+        let name = utf16_string(rest);
+        let size = (name.encode_utf16().count() + 1) * 2;
+        (name, size)
+    } else {
+        let name = ansi_string(rest);
+        (name.clone(), name.len() + 1)
+    };
+    fields.push(Field::new("Name", short_name));
+
+    // The extension block starts on the next 16 bit boundary.
+    let extension = 14 + name_size + (14 + name_size) % 2;
+    if let Some(block) = item.get(extension..) {
+        fields.extend(beef0004(block));
+    }
+    fields
+}
+
+/// Fields of a `0xBEEF0004` extension block, if `block` starts with one.
+fn beef0004(block: &[u8]) -> Vec<Field> {
+    let (Some(size), Some(version), Some(BEEF0004)) =
+        (read_u16(block, 0), read_u16(block, 2), read_u32(block, 4))
+    else {
+        return vec![];
+    };
+    let block = &block[..(size as usize).min(block.len())];
+
+    let mut fields = vec![];
+    if let Some(created) = read_u32(block, 8).and_then(dos_time) {
+        fields.push(Field::new("Created", created));
+    }
+    if let Some(accessed) = read_u32(block, 12).and_then(dos_time) {
+        fields.push(Field::new("Accessed", accessed));
+    }
+
+    // Fields inserted by later versions push the long name further back.
+    let mut offset = 18;
+    if version >= 7 {
+        // A 48 bit MFT entry number followed by a 16 bit sequence number.
+        if let (Some(low), Some(high), Some(sequence)) = (
+            read_u32(block, 20),
+            read_u16(block, 24),
+            read_u16(block, 26),
+        ) {
+            let entry = (high as u64) << 32 | low as u64;
+            fields.push(Field::new(
+                "MFT entry",
+                format!("{}, sequence {}", entry, sequence),
+            ));
+        }
+        offset += 2 + 8 + 8;
+    }
+    if version >= 3 {
+        offset += 2;
+    }
+    if version >= 9 {
+        offset += 4;
+    }
+    if version >= 8 {
+        offset += 4;
+    }
+    if let Some(name) = block.get(offset..).map(utf16_string) {
+        if !name.is_empty() {
+            fields.push(Field::new("Long name", name));
+        }
+    }
+    fields
+}
+
+/// Reads a single byte string up to the first NUL byte.
+fn ansi_string(data: &[u8]) -> String {
+    let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+    data[..end].iter().map(|b| *b as char).collect()
+}
+
+/// Formats a GUID stored in its mixed-endian binary form.
+pub fn format_guid(bytes: &[u8]) -> String {
+    format!(
+        "{:08X}-{:04X}-{:04X}-{}-{}",
+        u32::from_le_bytes(bytes[0..4].try_into().unwrap()),
+        u16::from_le_bytes(bytes[4..6].try_into().unwrap()),
+        u16::from_le_bytes(bytes[6..8].try_into().unwrap()),
+        hex(&bytes[8..10]),
+        hex(&bytes[10..16]),
+    )
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// Formats a FAT date and time, the date in the low and the time in the high 16 bits.
+/// Returns `None` for empty or invalid values.
+fn dos_time(value: u32) -> Option<String> {
+    let (date, time) = (value & 0xFFFF, value >> 16);
+    let day = NaiveDate::from_ymd_opt(1980 + (date >> 9) as i32, (date >> 5) & 0x0F, date & 0x1F)?;
+    let time = day.and_hms_opt((time >> 11) & 0x1F, (time >> 5) & 0x3F, (time & 0x1F) * 2)?;
+    Some(time.format("%Y-%m-%d %H:%M:%S").to_string())
+}
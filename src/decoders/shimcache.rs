@@ -0,0 +1,229 @@
+use notatin::cell_value::CellValue;
+
+use crate::decoders::{binary, format_filetime, path_matches, Decoder, Field};
+use crate::hive::utf16_string;
+use crate::hive::{read_u16, read_u32, read_u64};
+
+/// Header magic of the Windows 7 and Server 2008 R2 cache.
+const WIN7_MAGIC: u32 = 0xBADC_0FEE;
+/// Size of a Windows 7 x64 cache entry.
+const WIN7_X64_ENTRY_SIZE: usize = 48;
+/// Size of the header before the first entry of the Windows 7 and 8 caches.
+const HEADER_SIZE: usize = 0x80;
+
+/// The application compatibility cache, listing executables with their last modified times.
+pub struct ShimCache;
+
+impl Decoder for ShimCache {
+    fn name(&self) -> &'static str {
+        "ShimCache"
+    }
+
+    fn matches(&self, key_path: &str, value_name: &str) -> bool {
+        value_name.eq_ignore_ascii_case("AppCompatCache")
+            && path_matches(key_path, "*\\Control\\Session Manager\\AppCompatCache")
+    }
+
+    fn decode(&self, _value_name: &str, data: &CellValue) -> Option<Vec<Field>> {
+        let data = binary(data)?;
+        let entries = if read_u32(data, 0)? == WIN7_MAGIC {
+            windows7(data)?
+        } else if data.get(HEADER_SIZE..HEADER_SIZE + 4) == Some(b"00ts")
+            || data.get(HEADER_SIZE..HEADER_SIZE + 4) == Some(b"10ts")
+        {
+            windows8(&data[HEADER_SIZE..])
+        } else {
+            let header_size = read_u32(data, 0)? as usize;
+            let entries = data.get(header_size..)?;
+            if !entries.starts_with(b"10ts") {
+                return None;
+            }
+            windows10(entries)
+        };
+
+        let mut fields = vec![Field::new("Entries", entries.len().to_string())];
+        fields.extend(
+            entries
+                .into_iter()
+                .enumerate()
+                .map(|(index, (path, modified))| {
+                    Field::new(
+                        format!("#{}", index + 1),
+                        format!("{}  {}", format_filetime(modified), path),
+                    )
+                }),
+        );
+        Some(fields)
+    }
+}
+
+/// Entries of the Windows 10 and 11 cache: a signature, an unused field, the entry
+/// size and the path, followed by the modified time and data of variable size.
+fn windows10(mut data: &[u8]) -> Vec<(String, u64)> {
+    let mut entries = vec![];
+    while data.starts_with(b"10ts") {
+        let Some(entry_size) = read_u32(data, 8) else {
+            break;
+        };
+        let Some(entry) = data.get(12..12 + entry_size as usize) else {
+            break;
+        };
+        if let Some(parsed) = path_and_time(entry) {
+            entries.push(parsed);
+        }
+        data = &data[12 + entry_size as usize..];
+    }
+    entries
+}
+
+/// Entries of the Windows 8 (`00ts`) and 8.1 (`10ts`) cache, laid out like the
+/// Windows 10 ones but with two flags fields before the modified time. On 8.1
+/// the path is followed by the name of the package the executable belongs to.
+fn windows8(mut data: &[u8]) -> Vec<(String, u64)> {
+    let mut entries = vec![];
+    while data.starts_with(b"00ts") || data.starts_with(b"10ts") {
+        let has_package = data.starts_with(b"10ts");
+        let Some(entry_size) = read_u32(data, 8) else {
+            break;
+        };
+        let Some(entry) = data.get(12..12 + entry_size as usize) else {
+            break;
+        };
+        if let Some(parsed) = windows8_entry(entry, has_package) {
+            entries.push(parsed);
+        }
+        data = &data[12 + entry_size as usize..];
+    }
+    entries
+}
+
+/// Reads the path and modified time of a Windows 8 or 8.1 entry.
+fn windows8_entry(entry: &[u8], has_package: bool) -> Option<(String, u64)> {
+    let path_size = read_u16(entry, 0)? as usize;
+    let path = utf16_string(entry.get(2..2 + path_size)?);
+    let mut flags = 2 + path_size;
+    if has_package {
+        flags += 2 + read_u16(entry, flags)? as usize;
+    }
+    let modified = read_u64(entry, flags + 8)?;
+    Some((path, modified))
+}
+
+/// Entries of the Windows 7 x64 cache: a table of fixed-size records pointing at
+/// paths stored after it.
+fn windows7(data: &[u8]) -> Option<Vec<(String, u64)>> {
+    let count = read_u32(data, 4)? as usize;
+    let mut entries = vec![];
+    for index in 0..count {
+        let entry = HEADER_SIZE + index * WIN7_X64_ENTRY_SIZE;
+        let (Some(path_size), Some(path_offset), Some(modified)) = (
+            read_u16(data, entry),
+            read_u64(data, entry + 8),
+            read_u64(data, entry + 16),
+        ) else {
+            break;
+        };
+        let path_offset = path_offset as usize;
+        let path = data
+            .get(path_offset..path_offset.saturating_add(path_size as usize))
+            .map(utf16_string)
+            .unwrap_or_default();
+        entries.push((path, modified));
+    }
+    Some(entries)
+}
+
+/// Reads the path and modified time at the start of a Windows 10 entry.
+fn path_and_time(entry: &[u8]) -> Option<(String, u64)> {
+    let path_size = read_u16(entry, 0)? as usize;
+    let path = utf16_string(entry.get(2..2 + path_size)?);
+    let modified = read_u64(entry, 2 + path_size)?;
+    Some((path, modified))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODIFIED: u64 = 0x01D9_0000_0000_0000;
+
+    fn utf16(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    /// A Windows 8 or 8.1 cache holding one entry for `path`.
+    fn windows8_cache(signature: &[u8; 4], path: &str, package: Option<&str>) -> Vec<u8> {
+        let path = utf16(path);
+        let mut entry = (path.len() as u16).to_le_bytes().to_vec();
+        entry.extend(&path);
+        if let Some(package) = package {
+            let package = utf16(package);
+            entry.extend((package.len() as u16).to_le_bytes());
+            entry.extend(&package);
+        }
+        entry.extend([0xAA; 8]);
+        entry.extend(MODIFIED.to_le_bytes());
+        entry.extend(0u32.to_le_bytes());
+
+        let mut data = vec![0; HEADER_SIZE];
+        data.extend(signature);
+        data.extend([0; 4]);
+        data.extend((entry.len() as u32).to_le_bytes());
+        data.extend(entry);
+        data
+    }
+
+    #[test]
+    fn reads_windows8_entries() {
+        let data = windows8_cache(b"00ts", "C:\\Windows\\é.exe", None);
+        assert_eq!(
+            windows8(&data[HEADER_SIZE..]),
+            vec![("C:\\Windows\\é.exe".to_string(), MODIFIED)]
+        );
+    }
+
+    #[test]
+    fn skips_the_package_of_windows81_entries() {
+        let data = windows8_cache(b"10ts", "C:\\app.exe", Some("Microsoft.App_8wekyb3d8bbwe"));
+        assert_eq!(
+            windows8(&data[HEADER_SIZE..]),
+            vec![("C:\\app.exe".to_string(), MODIFIED)]
+        );
+    }
+
+    #[test]
+    fn stops_at_truncated_entries() {
+        let mut data = windows8_cache(b"10ts", "C:\\app.exe", Some("package"));
+        data.truncate(data.len() - 10);
+        assert_eq!(windows8(&data[HEADER_SIZE..]), vec![]);
+
+        // An entry whose sizes point past its end.
+        let mut data = windows8_cache(b"00ts", "C:\\app.exe", None);
+        data[HEADER_SIZE + 12] = 0xFF;
+        assert_eq!(windows8(&data[HEADER_SIZE..]), vec![]);
+
+        assert_eq!(windows10(b"10ts\0\0\0\0\xFF\xFF"), vec![]);
+        assert_eq!(windows7(&WIN7_MAGIC.to_le_bytes()), None);
+    }
+
+    #[test]
+    fn decodes_windows10_entries() {
+        let path = utf16("C:\\Program Files\\ツール.exe");
+        let mut entry = (path.len() as u16).to_le_bytes().to_vec();
+        entry.extend(&path);
+        entry.extend(MODIFIED.to_le_bytes());
+        entry.extend(0u32.to_le_bytes());
+        let mut data = 0x34u32.to_le_bytes().to_vec();
+        data.resize(0x34, 0);
+        data.extend(b"10ts");
+        data.extend([0; 4]);
+        data.extend((entry.len() as u32).to_le_bytes());
+        data.extend(entry);
+
+        let fields = ShimCache
+            .decode("AppCompatCache", &CellValue::Binary(data))
+            .unwrap();
+        assert_eq!(fields[0], Field::new("Entries", "1"));
+        assert!(fields[1].value.ends_with("C:\\Program Files\\ツール.exe"));
+    }
+}
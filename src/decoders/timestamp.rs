@@ -0,0 +1,230 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, Utc};
+use chrono_tz::Tz;
+use notatin::cell_value::CellValue;
+
+use crate::decoders::{filetime, path_matches};
+
+/// How a number encodes a time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    /// 100 nanosecond intervals since 1601-01-01 UTC.
+    Filetime,
+    /// Seconds since 1970-01-01 UTC.
+    Unix,
+}
+
+impl Encoding {
+    fn decode(self, number: u64) -> Option<DateTime<Utc>> {
+        match self {
+            Encoding::Filetime => filetime(number),
+            Encoding::Unix => DateTime::from_timestamp(i64::try_from(number).ok()?, 0),
+        }
+    }
+}
+
+/// Values known to hold a time: a key path pattern as taken by
+/// [`path_matches`], a value name and the encoding. A name of `*` stands for
+/// any value with binary data, which leaves out DWORDs such as the `Version`
+/// and `SequenceNumber` next to the times BAM and DAM record per program.
+const RULES: &[(&str, &str, Encoding)] = &[
+    ("**", "InstallDate", Encoding::Unix),
+    ("**", "InstallTime", Encoding::Filetime),
+    ("**", "LastLogon", Encoding::Filetime),
+    ("*\\Control\\Windows", "ShutdownTime", Encoding::Filetime),
+    (
+        "*\\Services\\bam\\State\\UserSettings\\*",
+        "*",
+        Encoding::Filetime,
+    ),
+    ("*\\Services\\bam\\UserSettings\\*", "*", Encoding::Filetime),
+    (
+        "*\\Services\\dam\\State\\UserSettings\\*",
+        "*",
+        Encoding::Filetime,
+    ),
+    ("*\\Services\\dam\\UserSettings\\*", "*", Encoding::Filetime),
+];
+
+/// A time found in value data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timestamp {
+    pub time: DateTime<Utc>,
+    pub encoding: Encoding,
+    /// Whether a rule for the value named the encoding, rather than the number looking like a time.
+    pub by_rule: bool,
+}
+
+impl Timestamp {
+    /// How the time was found, for the inspector.
+    pub fn source(&self) -> String {
+        let encoding = match self.encoding {
+            Encoding::Filetime => "FILETIME",
+            Encoding::Unix => "Unix time",
+        };
+        if self.by_rule {
+            format!("{}, known value", encoding)
+        } else {
+            format!("{}, guessed from the number", encoding)
+        }
+    }
+}
+
+/// Finds a time in the data of the value called `value_name` in the key at `key_path`.
+///
+/// Values a rule knows about are decoded as the rule says. Otherwise QWORDs and
+/// 8 byte binary values are taken for FILETIMEs, and DWORDs whose name mentions
+/// a time or date for Unix times, as long as the result is a plausible date.
+pub fn detect(key_path: &str, value_name: &str, data: &CellValue) -> Option<Timestamp> {
+    let number = number(data)?;
+    let rule = RULES.iter().find(|(path, name, _)| {
+        let name_matches = match *name {
+            "*" => matches!(data, CellValue::Binary(_)),
+            name => name.eq_ignore_ascii_case(value_name),
+        };
+        name_matches && path_matches(key_path, path)
+    });
+    if let Some((_, _, encoding)) = rule {
+        return Some(Timestamp {
+            time: encoding.decode(number)?,
+            encoding: *encoding,
+            by_rule: true,
+        });
+    }
+
+    let encoding = match data {
+        CellValue::U32(_) | CellValue::I32(_) => {
+            let name = value_name.to_lowercase();
+            if !name.contains("time") && !name.contains("date") {
+                return None;
+            }
+            Encoding::Unix
+        }
+        CellValue::Binary(bytes) if bytes.len() != 8 => return None,
+        _ => Encoding::Filetime,
+    };
+    let time = encoding.decode(number)?;
+    plausible(time).then_some(Timestamp {
+        time,
+        encoding,
+        by_rule: false,
+    })
+}
+
+/// The number held by numeric data, or by the first 8 bytes of binary data.
+fn number(data: &CellValue) -> Option<u64> {
+    match data {
+        CellValue::U32(value) => Some(*value as u64),
+        CellValue::I32(value) => u64::try_from(*value).ok(),
+        CellValue::U64(value) => Some(*value),
+        CellValue::I64(value) => u64::try_from(*value).ok(),
+        CellValue::Binary(bytes) => crate::hive::read_u64(bytes, 0),
+        _ => None,
+    }
+}
+
+/// Whether a guessed time falls in the years Windows registries are written in.
+fn plausible(time: DateTime<Utc>) -> bool {
+    let start = NaiveDate::from_ymd_opt(1995, 1, 1).unwrap();
+    let end = NaiveDate::from_ymd_opt(2050, 1, 1).unwrap();
+    (start..end).contains(&time.date_naive())
+}
+
+/// Timezone decoded times are shown in.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DisplayTimezone {
+    #[default]
+    Utc,
+    /// The timezone of the machine running the viewer.
+    Local,
+    Offset(FixedOffset),
+    Named(Tz),
+}
+
+impl DisplayTimezone {
+    pub fn format(&self, time: DateTime<Utc>) -> String {
+        const FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+        match self {
+            DisplayTimezone::Utc => time.format(&format!("{} UTC", FORMAT)).to_string(),
+            DisplayTimezone::Local => time
+                .with_timezone(&Local)
+                .format(&format!("{} %:z", FORMAT))
+                .to_string(),
+            DisplayTimezone::Offset(offset) => time
+                .with_timezone(offset)
+                .format(&format!("{} %:z", FORMAT))
+                .to_string(),
+            DisplayTimezone::Named(tz) => time
+                .with_timezone(tz)
+                .format(&format!("{} %Z", FORMAT))
+                .to_string(),
+        }
+    }
+}
+
+impl FromStr for DisplayTimezone {
+    type Err = String;
+
+    /// Accepts `UTC`, `local`, an offset such as `+02:00` or an IANA name such as `Europe/Berlin`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("utc") || s.eq_ignore_ascii_case("z") {
+            Ok(DisplayTimezone::Utc)
+        } else if s.eq_ignore_ascii_case("local") {
+            Ok(DisplayTimezone::Local)
+        } else if s.starts_with(['+', '-']) {
+            s.parse()
+                .map(DisplayTimezone::Offset)
+                .map_err(|_| format!("{} is not an offset like +02:00", s))
+        } else {
+            s.parse()
+                .map(DisplayTimezone::Named)
+                .map_err(|_| format!("unknown timezone {}", s))
+        }
+    }
+}
+
+impl Display for DisplayTimezone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisplayTimezone::Utc => f.write_str("UTC"),
+            DisplayTimezone::Local => f.write_str("local"),
+            DisplayTimezone::Offset(offset) => write!(f, "{}", offset),
+            DisplayTimezone::Named(tz) => f.write_str(tz.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BAM: &str = "ControlSet001\\Services\\bam\\State\\UserSettings\\S-1-5-21-1-2-3-1001";
+    /// 2023-01-01 00:00:00 UTC.
+    const FILETIME: u64 = 133_170_048_000_000_000;
+
+    #[test]
+    fn reads_bam_entries() {
+        let mut data = FILETIME.to_le_bytes().to_vec();
+        data.extend([0; 16]);
+        let time = detect(
+            BAM,
+            "\\Device\\HarddiskVolume2\\tool.exe",
+            &CellValue::Binary(data),
+        )
+        .unwrap();
+        assert!(time.by_rule);
+        assert_eq!(time.time.to_rfc3339(), "2023-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn skips_bam_counters() {
+        assert_eq!(detect(BAM, "Version", &CellValue::U32(1)), None);
+        assert_eq!(detect(BAM, "SequenceNumber", &CellValue::U32(9)), None);
+        assert_eq!(
+            detect(BAM, "tool.exe", &CellValue::Binary(vec![1, 2, 3])),
+            None
+        );
+    }
+}
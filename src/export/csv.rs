@@ -0,0 +1,95 @@
+use std::io::{self, Write};
+use std::ops::ControlFlow;
+
+use notatin::cell_key_node::CellKeyNode;
+use notatin::parser::Parser;
+
+use crate::export::{stored_data, values_of, ExportScope};
+use crate::hive::walk::{child_path, walk_keys};
+use crate::widgets::value_selector::{type_name, ValueCellPreview};
+
+const HEADER: [&str; 6] = ["Key", "Last written", "Value", "Type", "Data", "Size"];
+
+/// Writes one row for every value of `key` and its descendants, after a header row.
+/// Returns the number of keys visited.
+pub fn write_csv(
+    parser: &mut Parser,
+    key: CellKeyNode,
+    scope: &ExportScope,
+    out: &mut impl Write,
+) -> io::Result<usize> {
+    write_row(out, &HEADER.map(String::from))?;
+
+    let mut keys = 0;
+    let mut result = Ok(());
+    let path = scope.path.to_string();
+    let _ = walk_keys(parser, key, path, scope.include_deleted, |key, path| {
+        let path = child_path(scope.mount_point, path);
+        let last_written = key
+            .last_key_written_date_and_time()
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        for value in values_of(key, scope.include_deleted) {
+            let content = value.get_content().0;
+            let size = stored_data(&value).len();
+            let row = [
+                path.clone(),
+                last_written.clone(),
+                value.get_pretty_name(),
+                type_name(value.data_type).to_string(),
+                ValueCellPreview(value.data_type, content).to_string(),
+                size.to_string(),
+            ];
+            result = write_row(out, &row);
+            if result.is_err() {
+                return ControlFlow::Break(());
+            }
+        }
+        keys += 1;
+        ControlFlow::Continue(())
+    });
+    result?;
+    Ok(keys)
+}
+
+fn write_row(out: &mut impl Write, fields: &[String]) -> io::Result<()> {
+    let row = fields.iter().map(|field| quote(field)).collect::<Vec<_>>();
+    write!(out, "{}\r\n", row.join(","))
+}
+
+/// Quotes a field as RFC 4180 asks, when it contains a separator, quote or line break.
+///
+/// Fields that a spreadsheet would evaluate as a formula are prefixed with `'`,
+/// since names and data in a hive under examination cannot be trusted. Plain
+/// numbers such as negative DWORDs are left alone.
+fn quote(field: &str) -> String {
+    let field =
+        if field.starts_with(['=', '+', '-', '@', '\t', '\r']) && field.parse::<f64>().is_err() {
+            format!("'{}", field)
+        } else {
+            field.to_string()
+        };
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_fields() {
+        assert_eq!(quote("plain"), "plain");
+        assert_eq!(quote("a,b"), "\"a,b\"");
+        assert_eq!(quote("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(quote("=1+1"), "'=1+1");
+        assert_eq!(quote("-42"), "-42");
+        assert_eq!(quote("-1.5"), "-1.5");
+        assert_eq!(quote("-1+1"), "'-1+1");
+        assert_eq!(quote("\t=1+1"), "'\t=1+1");
+        assert_eq!(quote("\r=1+1"), "\"'\r=1+1\"");
+    }
+}
@@ -0,0 +1,58 @@
+use std::io::{self, Write};
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+/// Bytes per line of hex text.
+const HEX_LINE_LENGTH: usize = 32;
+
+/// How the bytes of a value are written to a file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DataFormat {
+    /// The bytes as they are.
+    Raw,
+    /// Base64 text on a single line.
+    Base64,
+    /// Lines of lowercase hex digits.
+    Hex,
+}
+
+impl DataFormat {
+    /// The format a file name's extension asks for, the bytes as they are unless
+    /// it is `.b64`, `.base64` or `.hex`.
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("b64" | "base64") => DataFormat::Base64,
+            Some("hex") => DataFormat::Hex,
+            _ => DataFormat::Raw,
+        }
+    }
+}
+
+/// Writes `data` to `out` in `format`.
+pub fn write_data(format: DataFormat, data: &[u8], out: &mut impl Write) -> io::Result<()> {
+    match format {
+        DataFormat::Raw => out.write_all(data),
+        DataFormat::Base64 => writeln!(out, "{}", STANDARD.encode(data)),
+        DataFormat::Hex => {
+            for line in data.chunks(HEX_LINE_LENGTH) {
+                for byte in line {
+                    write!(out, "{:02x}", byte)?;
+                }
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// SHA-256 digest of `data` in lowercase hex.
+pub fn sha256(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
@@ -0,0 +1,121 @@
+use std::io::{self, Write};
+use std::ops::ControlFlow;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use notatin::cell_key_node::CellKeyNode;
+use notatin::cell_key_value::CellKeyValue;
+use notatin::cell_value::CellValue;
+use notatin::parser::Parser;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::export::{stored_data, values_of, ExportScope};
+use crate::hive::walk::{child_path, walk_keys};
+use crate::widgets::value_selector::type_name;
+
+/// One exported key or value. Value fields are left out of key records.
+#[derive(Serialize)]
+struct Record {
+    path: String,
+    last_written: String,
+    state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Value>,
+    /// The stored bytes, base64 encoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    raw: Option<String>,
+}
+
+/// Writes a record for `key`, each of its descendants and each of their values.
+///
+/// With `lines` set every record goes on a line of its own (JSON Lines),
+/// otherwise the records are written as a single JSON array.
+/// Returns the number of keys written.
+pub fn write_json(
+    parser: &mut Parser,
+    key: CellKeyNode,
+    scope: &ExportScope,
+    lines: bool,
+    out: &mut impl Write,
+) -> io::Result<usize> {
+    let mut keys = 0;
+    let mut first = true;
+    let mut result = Ok(());
+    if !lines {
+        out.write_all(b"[")?;
+    }
+
+    let path = scope.path.to_string();
+    let _ = walk_keys(parser, key, path, scope.include_deleted, |key, path| {
+        let path = child_path(scope.mount_point, path);
+        let mut records = vec![key_record(key, &path)];
+        let values = values_of(key, scope.include_deleted);
+        records.extend(values.iter().map(|value| value_record(key, &path, value)));
+
+        for record in records {
+            let separator: &[u8] = match (lines, first) {
+                (true, _) => b"",
+                (false, true) => b"\n",
+                (false, false) => b",\n",
+            };
+            first = false;
+            result = out
+                .write_all(separator)
+                .and_then(|_| serde_json::to_writer(&mut *out, &record).map_err(io::Error::from))
+                .and_then(|_| if lines { out.write_all(b"\n") } else { Ok(()) });
+            if result.is_err() {
+                return ControlFlow::Break(());
+            }
+        }
+        keys += 1;
+        ControlFlow::Continue(())
+    });
+    result?;
+
+    if !lines {
+        out.write_all(b"\n]\n")?;
+    }
+    Ok(keys)
+}
+
+fn key_record(key: &CellKeyNode, path: &str) -> Record {
+    Record {
+        path: path.to_string(),
+        last_written: key.last_key_written_date_and_time().to_rfc3339(),
+        state: format!("{:?}", key.cell_state),
+        value_name: None,
+        data_type: None,
+        data: None,
+        raw: None,
+    }
+}
+
+fn value_record(key: &CellKeyNode, path: &str, value: &CellKeyValue) -> Record {
+    let content = value.get_content().0;
+    Record {
+        value_name: Some(value.detail.value_name()),
+        data_type: Some(type_name(value.data_type).to_string()),
+        data: Some(decoded_data(&content)),
+        raw: Some(STANDARD.encode(stored_data(value))),
+        state: format!("{:?}", value.cell_state),
+        ..key_record(key, path)
+    }
+}
+
+/// Value data as the closest JSON type. Binary data is only carried in `raw`.
+fn decoded_data(content: &CellValue) -> Value {
+    match content {
+        CellValue::None | CellValue::Binary(_) | CellValue::Error => Value::Null,
+        CellValue::String(string) => json!(string),
+        CellValue::MultiString(strings) => json!(strings),
+        CellValue::U32(number) => json!(number),
+        CellValue::I32(number) => json!(number),
+        CellValue::U64(number) => json!(number),
+        CellValue::I64(number) => json!(number),
+    }
+}
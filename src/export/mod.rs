@@ -0,0 +1,116 @@
+use std::io::{self, Write};
+use std::path::Path;
+
+use clap::ValueEnum;
+use notatin::cell_key_node::CellKeyNode;
+use notatin::cell_key_value::CellKeyValue;
+use notatin::cell_value::CellValue;
+use notatin::parser::Parser;
+
+pub mod csv;
+pub mod data;
+pub mod json;
+pub mod reg;
+
+/// File formats a key subtree can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ExportFormat {
+    /// Registry editor file that regedit can import.
+    Reg,
+    /// A single JSON array of records.
+    Json,
+    /// One JSON record per line.
+    Jsonl,
+    /// One row per value, for spreadsheets.
+    Csv,
+}
+
+impl ExportFormat {
+    /// The format a file name's extension asks for.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "reg" => Some(ExportFormat::Reg),
+            "json" => Some(ExportFormat::Json),
+            "jsonl" | "ndjson" => Some(ExportFormat::Jsonl),
+            "csv" => Some(ExportFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// What to export and where the hive is mounted.
+pub struct ExportScope<'a> {
+    /// Path of the exported key below the root key.
+    pub path: &'a str,
+    /// Key the hive's root stands for, such as `HKEY_LOCAL_MACHINE\SOFTWARE`.
+    pub mount_point: &'a str,
+    /// Also export recovered deleted keys and values.
+    pub include_deleted: bool,
+}
+
+/// Writes `key` and all of its descendants to `out` in `format`.
+/// Returns the number of keys written.
+pub fn export(
+    format: ExportFormat,
+    parser: &mut Parser,
+    key: CellKeyNode,
+    scope: &ExportScope,
+    out: &mut impl Write,
+) -> io::Result<usize> {
+    match format {
+        ExportFormat::Reg => reg::write_reg(parser, key, scope, out),
+        ExportFormat::Json => json::write_json(parser, key, scope, false, out),
+        ExportFormat::Jsonl => json::write_json(parser, key, scope, true, out),
+        ExportFormat::Csv => csv::write_csv(parser, key, scope, out),
+    }
+}
+
+/// The values of `key`, leaving out recovered deleted values unless `include_deleted` is set.
+pub fn values_of(key: &CellKeyNode, include_deleted: bool) -> Vec<CellKeyValue> {
+    key.value_iter()
+        .filter(|value| include_deleted || !value.cell_state.is_deleted())
+        .collect::<Vec<CellKeyValue>>()
+}
+
+/// The data of `value` as stored in the hive, as far as notatin read it: the
+/// data of numbers is cut to the size of their type. Values notatin kept no
+/// data for fall back to [`raw_data`].
+pub fn stored_data(value: &CellKeyValue) -> Vec<u8> {
+    value
+        .detail
+        .value_bytes()
+        .unwrap_or_else(|| raw_data(&value.get_content().0))
+}
+
+/// Re-encodes decoded value data the way Windows stores it. This is not always
+/// what is stored: anything notatin drops while decoding, such as data after
+/// a string's terminating NUL, is missing. Use [`stored_data`] where possible.
+///
+/// Strings are written as NUL-terminated UTF-16LE, multi-strings with an
+/// additional terminating NUL, and numbers in little-endian byte order.
+pub fn raw_data(value: &CellValue) -> Vec<u8> {
+    match value {
+        CellValue::None | CellValue::Error => vec![],
+        CellValue::Binary(bytes) => bytes.clone(),
+        CellValue::String(string) => utf16_bytes(string),
+        CellValue::MultiString(strings) => {
+            let mut bytes: Vec<u8> = strings.iter().flat_map(|s| utf16_bytes(s)).collect();
+            bytes.extend([0, 0]);
+            bytes
+        }
+        CellValue::U32(number) => number.to_le_bytes().to_vec(),
+        CellValue::I32(number) => number.to_le_bytes().to_vec(),
+        CellValue::U64(number) => number.to_le_bytes().to_vec(),
+        CellValue::I64(number) => number.to_le_bytes().to_vec(),
+    }
+}
+
+/// `string` as UTF-16LE followed by a NUL character.
+fn utf16_bytes(string: &str) -> Vec<u8> {
+    string
+        .encode_utf16()
+        .chain([0])
+        .flat_map(u16::to_le_bytes)
+        .collect()
+}
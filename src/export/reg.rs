@@ -0,0 +1,162 @@
+use std::io::{self, Write};
+use std::ops::ControlFlow;
+
+use notatin::cell::CellState;
+use notatin::cell_key_node::CellKeyNode;
+use notatin::cell_key_value::{CellKeyValue, CellKeyValueDataTypes};
+use notatin::cell_value::CellValue;
+use notatin::parser::Parser;
+
+use crate::export::{raw_data, stored_data, values_of, ExportScope};
+use crate::hive::walk::{child_path, walk_keys};
+
+/// First line of every file regedit imports as Unicode.
+const HEADER: &str = "Windows Registry Editor Version 5.00";
+
+/// Regedit breaks hex data after this many characters per line.
+const LINE_WIDTH: usize = 80;
+
+/// Writes `key` and all of its descendants in the `.reg` format regedit imports.
+/// Recovered deleted keys and values are commented out, so that importing the
+/// file does not bring them back. Returns the number of keys written.
+pub fn write_reg(
+    parser: &mut Parser,
+    key: CellKeyNode,
+    scope: &ExportScope,
+    out: &mut impl Write,
+) -> io::Result<usize> {
+    // regedit expects UTF-16LE with a byte order mark.
+    let mut text = String::new();
+    text.push_str(HEADER);
+    text.push_str("\r\n");
+
+    let mut keys = 0;
+    // Paths of the deleted keys visited so far, whose subkeys are gone as well.
+    let mut deleted_keys: Vec<String> = vec![];
+    let path = scope.path.to_string();
+    let _ = walk_keys(parser, key, path, scope.include_deleted, |key, path| {
+        let key_deleted = is_deleted(&key.cell_state)
+            || deleted_keys
+                .iter()
+                .any(|deleted| path.starts_with(&format!("{}\\", deleted)));
+        let mut lines = vec![format!("[{}]", child_path(scope.mount_point, path))];
+        for value in values_of(key, scope.include_deleted) {
+            let line = value_line(&value);
+            if is_deleted(&value.cell_state) && !key_deleted {
+                lines.push("; Recovered deleted value:".to_string());
+                lines.push(commented(&line));
+            } else {
+                lines.push(line);
+            }
+        }
+
+        text.push_str("\r\n");
+        if key_deleted {
+            deleted_keys.push(path.to_string());
+            text.push_str("; Recovered deleted key:\r\n");
+            lines = lines.iter().map(|line| commented(line)).collect();
+        }
+        for line in lines {
+            text.push_str(&line);
+            text.push_str("\r\n");
+        }
+        keys += 1;
+        ControlFlow::Continue(())
+    });
+
+    out.write_all(&[0xFF, 0xFE])?;
+    for unit in text.encode_utf16() {
+        out.write_all(&unit.to_le_bytes())?;
+    }
+    Ok(keys)
+}
+
+/// One `"name"=data` line, with long hex data continued over several lines.
+fn value_line(value: &CellKeyValue) -> String {
+    let name = match value.detail.value_name().as_str() {
+        "" => "@".to_string(),
+        name => format!("\"{}\"", escape(name)),
+    };
+    let content = value.get_content().0;
+    let stored = stored_data(value);
+
+    // Strings and DWORDs are written as text only if that loses nothing of
+    // the stored data, anything else as hex with the stored data type.
+    let data = match (value.data_type, &content) {
+        (CellKeyValueDataTypes::REG_SZ, CellValue::String(string))
+            if !string.chars().any(char::is_control) && raw_data(&content) == stored =>
+        {
+            format!("\"{}\"", escape(string))
+        }
+        (CellKeyValueDataTypes::REG_DWORD, CellValue::U32(number)) if stored.len() == 4 => {
+            format!("dword:{:08x}", number)
+        }
+        (CellKeyValueDataTypes::REG_DWORD, CellValue::I32(number)) if stored.len() == 4 => {
+            format!("dword:{:08x}", number)
+        }
+        (CellKeyValueDataTypes::REG_BIN, _) => hex_data("hex:", &stored, name.len()),
+        _ => hex_data(
+            &format!("hex({:x}):", value.detail.data_type_raw()),
+            &stored,
+            name.len(),
+        ),
+    };
+    format!("{}={}", name, data)
+}
+
+/// Bytes as comma-separated hex pairs, wrapped the way regedit wraps them.
+fn hex_data(prefix: &str, bytes: &[u8], name_width: usize) -> String {
+    let mut data = prefix.to_string();
+    // Account for the value name and the `=` in front of the data.
+    let mut column = name_width + 1 + prefix.len();
+    for (index, byte) in bytes.iter().enumerate() {
+        data.push_str(&format!("{:02x}", byte));
+        column += 2;
+        if index + 1 < bytes.len() {
+            data.push(',');
+            column += 1;
+            if column >= LINE_WIDTH - 4 {
+                data.push_str("\\\r\n  ");
+                column = 2;
+            }
+        }
+    }
+    data
+}
+
+fn is_deleted(cell_state: &CellState) -> bool {
+    matches!(
+        cell_state,
+        CellState::DeletedPrimaryFile
+            | CellState::DeletedPrimaryFileSlack
+            | CellState::DeletedTransactionLog
+    )
+}
+
+/// Comments out `lines`, including the continuation lines of hex data.
+fn commented(lines: &str) -> String {
+    format!(";{}", lines.replace("\r\n", "\r\n;"))
+}
+
+/// Escapes backslashes and quotes inside a quoted name or string.
+fn escape(string: &str) -> String {
+    string.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_and_comments_hex_data() {
+        let data = hex_data("hex(3e7):", &[0xAB; 30], 6);
+        let lines: Vec<&str> = data.split("\r\n").collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("hex(3e7):ab,ab,") && lines[0].ends_with(",\\"));
+        assert!(lines.iter().all(|line| 6 + 1 + line.len() <= LINE_WIDTH));
+        assert!(lines[1].starts_with("  ab"));
+
+        let commented = commented(&format!("\"Name\"={}", data));
+        assert!(commented.split("\r\n").all(|line| line.starts_with(';')));
+    }
+}
@@ -1,82 +1,398 @@
+use crate::app::keymap::{Action, Context};
+use crate::app::mouse::Target;
+use crate::app::prompt::{Prompt, PromptKind};
 use crate::app::state::FocusedPane::*;
 use crate::app::{App, AppResult};
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crate::tui::copy_to_clipboard;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use std::path::Path;
+
+/// Rows the selection moves by when paging through a list.
+const PAGE_ROWS: isize = 10;
 
 /// Handles the key events and updates the state of [`App`].
 pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
-    match key_event.code {
-        // Exit application on `ESC` or `q`
-        KeyCode::Esc | KeyCode::Char('q') => {
-            app.quit();
-        }
-        // Exit application on `Ctrl-C`
-        KeyCode::Char('c') | KeyCode::Char('C') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                app.quit();
+    app.state.message = None;
+    if app.state.prompt.is_some() {
+        return handle_prompt_key_events(key_event, app);
+    }
+
+    // The overlays take every key, the panes fall back to the global keys.
+    let context = app.state.key_context();
+    let keymap = &app.state.keymap;
+    let Some(action) = keymap.action(context, key_event) else {
+        return match keymap.action(Context::Global, key_event) {
+            Some(action) if context.is_pane() => handle_global_action(action, app),
+            _ => Ok(()),
+        };
+    };
+    match context {
+        Context::Help => handle_help_action(action, app),
+        Context::Bookmarks => handle_bookmark_picker_action(action, app),
+        Context::Timeline => handle_timeline_action(action, app),
+        Context::KeySelector => handle_key_selector_action(action, app),
+        Context::ValueSelector => handle_value_selector_action(action, app),
+        Context::ValueInspector => handle_value_inspector_action(action, app),
+        Context::Global => handle_global_action(action, app),
+    }
+}
+
+pub fn handle_global_action(action: Action, app: &mut App) -> AppResult<()> {
+    match action {
+        Action::Quit => app.quit(),
+        Action::Back => go_back(app),
+        Action::Forward => go_forward(app),
+        Action::NextPane => app.state.focus_next_tab(),
+        Action::PreviousPane => app.state.focus_previous_tab(),
+        Action::Search => app.state.prompt = Some(Prompt::new(PromptKind::Search)),
+        Action::GoTo => app.state.prompt = Some(Prompt::new(PromptKind::GoTo)),
+        Action::Timeline => app.state.timeline.toggle(&app.state.source),
+        Action::ToggleBookmark => app.state.toggle_bookmark(),
+        Action::Bookmarks => app.state.open_bookmarks(),
+        Action::Export => {
+            // Key names may contain characters that are path separators on the host.
+            let name = app
+                .state
+                .navigation
+                .selected_key()
+                .key_name
+                .replace(['/', '\\'], "_");
+            app.state.prompt = Some(Prompt::with_input(
+                PromptKind::Export,
+                format!("{}.reg", name),
+            ));
+        }
+        Action::CopyPath => {
+            let path = app.state.navigation.selected_path();
+            copy_to_clipboard(&path)?;
+            app.state.message = Some(format!("copied {}", path));
+        }
+        Action::NextHit => app.state.change_search_hit_by(1),
+        Action::PreviousHit => app.state.change_search_hit_by(-1),
+        Action::Help => app.state.help.show(app.state.key_context()),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handles the mouse events and updates the state of [`App`].
+pub fn handle_mouse_events(mouse_event: MouseEvent, app: &mut App) -> AppResult<()> {
+    // The prompt and the overlays take all input while they are shown.
+    if app.state.prompt.is_some()
+        || app.state.help.visible
+        || app.state.bookmarks.visible
+        || app.state.timeline.visible
+    {
+        return Ok(());
+    }
+    let (column, row) = (mouse_event.column, mouse_event.row);
+    let Some(target) = app.state.mouse.target(column, row) else {
+        return Ok(());
+    };
+    match mouse_event.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            app.state.message = None;
+            let double_click = app.state.mouse.click(column, row);
+            match target {
+                Target::Breadcrumb(path) => match app.state.navigation.find_key(&path) {
+                    Some(key) => {
+                        app.state.navigation.select_key(key);
+                        app.state.focused_pane = KeySelector;
+                    }
+                    None => app.state.message = Some(format!("key {} not found", path)),
+                },
+                Target::Row(pane, row) => {
+                    app.state.focused_pane = pane;
+                    let navigation = &mut app.state.navigation;
+                    if pane == KeySelector {
+                        let index = navigation.table_states.key_selector_state.offset() + row;
+                        if index < navigation.current_subkeys.len() {
+                            navigation.select_subkey_at(index);
+                            if double_click {
+                                navigation.enter_key();
+                            }
+                        }
+                    } else {
+                        let index = navigation.table_states.value_selector_state.offset() + row;
+                        if index < navigation.current_values.len() {
+                            navigation.select_value_at(index);
+                            if double_click {
+                                app.state.focused_pane = ValueInspector;
+                            }
+                        }
+                    }
+                }
+                Target::Pane(pane) => app.state.focused_pane = pane,
             }
         }
-        KeyCode::Tab => {
-            if key_event.modifiers == KeyModifiers::SHIFT {
-                app.state.focus_previous_tab()
-            } else {
-                app.state.focus_next_tab()
+        MouseEventKind::ScrollDown => scroll(target, 1, app),
+        MouseEventKind::ScrollUp => scroll(target, -1, app),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Moves the selection of the pane under the mouse pointer `n` rows down (or up for negative `n`).
+fn scroll(target: Target, n: isize, app: &mut App) {
+    let pane = match target {
+        Target::Row(pane, _) | Target::Pane(pane) => pane,
+        Target::Breadcrumb(_) => return,
+    };
+    match pane {
+        KeySelector => app.state.navigation.change_subkey_by(n),
+        ValueSelector => app.state.navigation.change_value_by(n),
+        ValueInspector => {
+            if let Some(value) = &app.state.navigation.selected_value {
+                let inspector = &mut app.state.inspector;
+                inspector.sync(value);
+                let len = inspector.data(value).len();
+                inspector.move_cursor_rows_by(len, n);
             }
         }
+    }
+}
 
-        // Other handlers you could add here.
-        _ => match app.state.focused_pane {
-            KeySelector => handle_key_selector_key_events(key_event, app),
-            ValueSelector => handle_value_selector_key_events(key_event, app),
-            ValueInspector => handle_value_inspector_key_events(key_event, app),
-        }?,
+/// Shows or hides recovered deleted entries, which only exist when the hive
+/// was opened with `-d`.
+fn toggle_deleted(app: &mut App) {
+    if app.state.source.recover_deleted {
+        app.state.navigation.toggle_deleted();
+    } else {
+        app.state.message = Some("deleted keys and values are only recovered with -d".to_string());
+    }
+}
+
+fn go_back(app: &mut App) {
+    if !app.state.navigation.go_back() {
+        app.state.message = Some("no earlier key in the history".to_string());
+    }
+}
+
+fn go_forward(app: &mut App) {
+    if !app.state.navigation.go_forward() {
+        app.state.message = Some("no later key in the history".to_string());
     }
-    Ok(())
 }
 
-pub fn handle_key_selector_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+pub fn handle_prompt_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+    let Some(prompt) = &mut app.state.prompt else {
+        return Ok(());
+    };
+    let control = key_event.modifiers == KeyModifiers::CONTROL;
+
     match key_event.code {
-        KeyCode::Char('l') => app.state.navigation.enter_key(),
-        KeyCode::Char('h') => app.state.navigation.leave_key(),
-        KeyCode::Char('j') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                app.state.navigation.change_subkey_by(10)
-            } else {
-                app.state.navigation.change_subkey_by(1)
+        KeyCode::Esc => app.state.prompt = None,
+        KeyCode::Char('c') if control => app.state.prompt = None,
+        KeyCode::Enter => {
+            if let Some(prompt) = app.state.prompt.take() {
+                submit_prompt(prompt, app)?;
             }
         }
-        KeyCode::Char('k') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                app.state.navigation.change_subkey_by(-10)
+        KeyCode::Backspace => {
+            prompt.input.pop();
+        }
+        KeyCode::Tab if prompt.kind == PromptKind::GoTo => {
+            (prompt.input, prompt.completions) = app
+                .state
+                .navigation
+                .complete_path(&prompt.input, &app.state.source.mount_point)
+        }
+        // Search options can be toggled while typing the query.
+        KeyCode::Char('r') if control && prompt.kind == PromptKind::Search => {
+            app.state.search.options.regex = !app.state.search.options.regex
+        }
+        KeyCode::Char('t') if control && prompt.kind == PromptKind::Search => {
+            app.state.search.options.case_sensitive = !app.state.search.options.case_sensitive
+        }
+        KeyCode::Char(c) if !control => prompt.input.push(c),
+        _ => return Ok(()),
+    }
+
+    // Search while the query is typed. The search starts on the next tick,
+    // so fast typing does not start one for every key.
+    if let Some(prompt) = &app.state.prompt {
+        if prompt.kind == PromptKind::Search {
+            app.state.search.pending = Some(prompt.input.clone());
+        }
+    }
+    Ok(())
+}
+
+fn submit_prompt(prompt: Prompt, app: &mut App) -> AppResult<()> {
+    // An empty timeline filter clears it, anything else needs some input.
+    let clears_filter = matches!(
+        prompt.kind,
+        PromptKind::TimelinePath | PromptKind::TimelineRange
+    );
+    if prompt.input.is_empty() && !clears_filter {
+        return Ok(());
+    }
+    match prompt.kind {
+        PromptKind::Search => app.state.start_pending_search(),
+        PromptKind::GoTo => {
+            if app.state.navigation.go_to(&prompt.input) {
+                app.state.focused_pane = KeySelector;
             } else {
-                app.state.navigation.change_subkey_by(-1)
+                app.state.message = Some(format!("key {} not found", prompt.input));
+            }
+        }
+        PromptKind::TimelinePath => {
+            app.state.timeline.filter.path = prompt.input;
+            app.state.timeline.refresh();
+        }
+        PromptKind::TimelineRange => match app.state.timeline.filter.set_range(&prompt.input) {
+            Ok(()) => app.state.timeline.refresh(),
+            Err(e) => app.state.message = Some(format!("invalid time range: {}", e)),
+        },
+        PromptKind::FindInValue => app.state.find_in_value(&prompt.input),
+        PromptKind::SaveValue => {
+            if let Err(e) = app.state.save_selected_value(Path::new(&prompt.input)) {
+                app.state.message = Some(format!("saving failed: {}", e));
+            }
+        }
+        PromptKind::Export => {
+            if let Err(e) = app.state.export_selected_key(Path::new(&prompt.input)) {
+                app.state.message = Some(format!("export failed: {}", e));
             }
         }
+    }
+    Ok(())
+}
+
+pub fn handle_help_action(action: Action, app: &mut App) -> AppResult<()> {
+    let help = &mut app.state.help;
+    match action {
+        Action::Close => help.visible = false,
+        Action::Down => help.scroll_by(1),
+        Action::Up => help.scroll_by(-1),
+        Action::PageDown => help.scroll_by(PAGE_ROWS),
+        Action::PageUp => help.scroll_by(-PAGE_ROWS),
         _ => {}
     }
     Ok(())
 }
 
-pub fn handle_value_selector_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
-    match key_event.code {
-        KeyCode::Char('j') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                app.state.navigation.change_value_by(10)
-            } else {
-                app.state.navigation.change_value_by(1)
+pub fn handle_bookmark_picker_action(action: Action, app: &mut App) -> AppResult<()> {
+    let bookmarks = &mut app.state.bookmarks;
+    match action {
+        Action::Close => bookmarks.visible = false,
+        Action::Quit => app.quit(),
+        Action::Open => app.state.open_selected_bookmark(),
+        Action::Remove => {
+            if let Err(e) = bookmarks.remove_selected() {
+                app.state.message = Some(format!("cannot save bookmarks: {}", e));
             }
         }
-        KeyCode::Char('k') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                app.state.navigation.change_value_by(-10)
-            } else {
-                app.state.navigation.change_value_by(-1)
-            }
+        Action::Down => bookmarks.change_selection_by(1),
+        Action::Up => bookmarks.change_selection_by(-1),
+        Action::Help => app.state.help.show(app.state.key_context()),
+        _ => {}
+    }
+    Ok(())
+}
+
+pub fn handle_timeline_action(action: Action, app: &mut App) -> AppResult<()> {
+    let timeline = &mut app.state.timeline;
+    match action {
+        Action::Close => timeline.visible = false,
+        Action::Quit => app.quit(),
+        Action::Open => app.state.open_timeline_entry(),
+        Action::CycleOrder => timeline.cycle_order(),
+        Action::FilterPath => {
+            let input = timeline.filter.path.clone();
+            app.state.prompt = Some(Prompt::with_input(PromptKind::TimelinePath, input));
+        }
+        Action::FilterRange => {
+            let input = timeline.filter.range();
+            app.state.prompt = Some(Prompt::with_input(PromptKind::TimelineRange, input));
+        }
+        Action::PageDown => timeline.change_selection_by(PAGE_ROWS),
+        Action::PageUp => timeline.change_selection_by(-PAGE_ROWS),
+        Action::Down => timeline.change_selection_by(1),
+        Action::Up => timeline.change_selection_by(-1),
+        Action::Help => app.state.help.show(app.state.key_context()),
+        _ => {}
+    }
+    Ok(())
+}
+
+pub fn handle_key_selector_action(action: Action, app: &mut App) -> AppResult<()> {
+    let navigation = &mut app.state.navigation;
+    match action {
+        Action::Open => app.state.navigation.enter_key(),
+        Action::Parent => navigation.leave_key(),
+        Action::ToggleDeleted => toggle_deleted(app),
+        Action::ToggleChangesOnly => navigation.toggle_changes_only(),
+        Action::ToggleSecurity => app.state.toggle_key_preview(),
+        Action::Down => navigation.change_subkey_by(1),
+        Action::Up => navigation.change_subkey_by(-1),
+        Action::PageDown => navigation.change_subkey_by(PAGE_ROWS),
+        Action::PageUp => navigation.change_subkey_by(-PAGE_ROWS),
+        Action::First => navigation.select_subkey_at(0),
+        Action::Last => {
+            navigation.select_subkey_at(navigation.current_subkeys.len().saturating_sub(1))
         }
         _ => {}
     }
     Ok(())
 }
 
-pub fn handle_value_inspector_key_events(_key_event: KeyEvent, _app: &mut App) -> AppResult<()> {
+pub fn handle_value_selector_action(action: Action, app: &mut App) -> AppResult<()> {
+    let navigation = &mut app.state.navigation;
+    if navigation.current_values.is_empty() {
+        return Ok(());
+    }
+    match action {
+        Action::Down => navigation.change_value_by(1),
+        Action::Up => navigation.change_value_by(-1),
+        Action::PageDown => navigation.change_value_by(PAGE_ROWS),
+        Action::PageUp => navigation.change_value_by(-PAGE_ROWS),
+        Action::First => navigation.select_value_at(0),
+        Action::Last => navigation.select_value_at(navigation.current_values.len() - 1),
+        Action::Open => app.state.focused_pane = ValueInspector,
+        _ => {}
+    }
+    Ok(())
+}
+
+pub fn handle_value_inspector_action(action: Action, app: &mut App) -> AppResult<()> {
+    let Some(value) = &app.state.navigation.selected_value else {
+        return Ok(());
+    };
+    let inspector = &mut app.state.inspector;
+    inspector.sync(value);
+    let len = inspector.data(value).len();
+    match action {
+        Action::OlderVersion => inspector.change_version_by(value, 1),
+        Action::NewerVersion => inspector.change_version_by(value, -1),
+        Action::ToggleRaw => inspector.raw = !inspector.raw,
+        Action::ToggleExpand => app.state.toggle_expand(),
+        Action::ToggleContent => inspector.toggle_content(),
+        // Moving around the hex view.
+        Action::PageDown => inspector.move_cursor_pages_by(len, 1),
+        Action::PageUp => inspector.move_cursor_pages_by(len, -1),
+        Action::Down => inspector.move_cursor_rows_by(len, 1),
+        Action::Up => inspector.move_cursor_rows_by(len, -1),
+        Action::Right => inspector.move_cursor_by(len, 1),
+        Action::Left => inspector.move_cursor_by(len, -1),
+        Action::First => inspector.cursor = 0,
+        Action::Last => inspector.cursor = len.saturating_sub(1),
+        Action::Select => inspector.toggle_selection(),
+        Action::Find => app.state.prompt = Some(Prompt::new(PromptKind::FindInValue)),
+        Action::FindNext => app.state.find_next_in_value(true, true),
+        Action::FindPrevious => app.state.find_next_in_value(false, true),
+        Action::Save => {
+            // Value names may contain characters that are path separators on the host.
+            let name = match value.detail.value_name().as_str() {
+                "" => "default".to_string(),
+                name => name.replace(['/', '\\'], "_"),
+            };
+            app.state.prompt = Some(Prompt::with_input(
+                PromptKind::SaveValue,
+                format!("{}.bin", name),
+            ));
+        }
+        _ => {}
+    }
     Ok(())
 }
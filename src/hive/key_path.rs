@@ -0,0 +1,139 @@
+/// Root keys as written in regedit and reports, and whether the component
+/// after them names the mounted hive (`HKLM\SOFTWARE`) rather than a key in it.
+const ROOT_KEYS: &[(&str, bool)] = &[
+    ("HKEY_LOCAL_MACHINE", true),
+    ("HKLM", true),
+    ("HKEY_USERS", true),
+    ("HKU", true),
+    ("HKEY_CURRENT_USER", false),
+    ("HKCU", false),
+    ("HKEY_CLASSES_ROOT", false),
+    ("HKCR", false),
+    ("HKEY_CURRENT_CONFIG", false),
+    ("HKCC", false),
+];
+
+/// Splits a user supplied key path into components below the root key of the hive.
+///
+/// Leading `Computer\`, root keys such as `HKLM\SOFTWARE\` and the name of the
+/// hive's own root key (`root_name`) are dropped, as are empty components.
+pub fn normalize(path: &str, root_name: &str) -> Vec<String> {
+    let mut components: Vec<&str> = path
+        .trim()
+        .split('\\')
+        .filter(|component| !component.is_empty())
+        .collect();
+
+    if components
+        .first()
+        .is_some_and(|first| first.eq_ignore_ascii_case("Computer"))
+    {
+        components.remove(0);
+    }
+
+    let root_key = components.first().and_then(|first| {
+        ROOT_KEYS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(first))
+    });
+    if let Some((_, mounted)) = root_key {
+        let skip = if *mounted { 2 } else { 1 };
+        components.drain(..skip.min(components.len()));
+    } else if components
+        .first()
+        .is_some_and(|first| first.eq_ignore_ascii_case(root_name))
+    {
+        components.remove(0);
+    }
+
+    components.into_iter().map(str::to_string).collect()
+}
+
+/// Names the component typed after `head` can take other than the name of a
+/// key in the hive: a root key for the first component, and the name the hive
+/// is mounted under (from `mount_point`) after a root key that mounts hives.
+/// Also tells whether the component can be a key in the hive.
+pub fn prefix_completions(head: &str, mount_point: &str) -> (Vec<String>, bool) {
+    let mut components: Vec<&str> = head
+        .split('\\')
+        .filter(|component| !component.is_empty())
+        .collect();
+    if components
+        .first()
+        .is_some_and(|first| first.eq_ignore_ascii_case("Computer"))
+    {
+        components.remove(0);
+    }
+
+    match components.as_slice() {
+        [] => (
+            ROOT_KEYS.iter().map(|(name, _)| name.to_string()).collect(),
+            true,
+        ),
+        [root]
+            if ROOT_KEYS
+                .iter()
+                .any(|(name, mounted)| *mounted && name.eq_ignore_ascii_case(root)) =>
+        {
+            let mount_name = mount_point
+                .split('\\')
+                .nth(1)
+                .map(str::to_string)
+                .into_iter()
+                .collect();
+            (mount_name, false)
+        }
+        _ => (vec![], true),
+    }
+}
+
+/// Path below the root key for one of notatin's `\ROOT\Sub\Key` style key paths.
+pub fn relative(path: &str) -> String {
+    path.trim_start_matches('\\')
+        .split_once('\\')
+        .map(|(_, rest)| rest.to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_drops_prefixes() {
+        let expected = ["Microsoft", "Windows"];
+        for path in [
+            "Microsoft\\Windows",
+            "\\Microsoft\\\\Windows\\",
+            "HKLM\\SOFTWARE\\Microsoft\\Windows",
+            "Computer\\HKEY_LOCAL_MACHINE\\SOFTWARE\\Microsoft\\Windows",
+            "hkcu\\Microsoft\\Windows",
+            "ROOT\\Microsoft\\Windows",
+        ] {
+            assert_eq!(normalize(path, "ROOT"), expected, "{}", path);
+        }
+        assert!(normalize("HKLM", "ROOT").is_empty());
+    }
+
+    #[test]
+    fn relative_drops_root_name() {
+        assert_eq!(relative("\\ROOT\\Microsoft\\Windows"), "Microsoft\\Windows");
+        assert_eq!(relative("\\ROOT"), "");
+    }
+
+    #[test]
+    fn completes_root_key_and_mount_name() {
+        let (names, keys) = prefix_completions("", "HKEY_LOCAL_MACHINE\\SOFTWARE");
+        assert!(keys);
+        assert!(names.contains(&"HKLM".to_string()));
+
+        let (names, keys) = prefix_completions("Computer\\HKLM\\", "HKEY_LOCAL_MACHINE\\SOFTWARE");
+        assert_eq!((names, keys), (vec!["SOFTWARE".to_string()], false));
+
+        let (names, keys) = prefix_completions("HKCU\\", "HKEY_CURRENT_USER");
+        assert_eq!((names, keys), (vec![], true));
+
+        let (names, keys) = prefix_completions("HKLM\\SOFTWARE\\", "HKEY_LOCAL_MACHINE\\SOFTWARE");
+        assert_eq!((names, keys), (vec![], true));
+    }
+}
@@ -0,0 +1,267 @@
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use notatin::parser::Parser;
+use notatin::parser_builder::ParserBuilder;
+
+use crate::app::AppResult;
+use crate::cli::HiveArgs;
+
+pub mod key_path;
+pub mod security;
+pub mod walk;
+
+/// Every hive and transaction log starts with a base block carrying this signature.
+const REGF_SIGNATURE: &[u8; 4] = b"regf";
+
+/// Keys Windows loads the hives of users under, by file name without extension.
+/// Other hives are loaded under `HKEY_LOCAL_MACHINE`.
+const USER_MOUNT_POINTS: &[(&str, &str)] = &[
+    ("NTUSER", "HKEY_CURRENT_USER"),
+    ("UsrClass", "HKEY_CURRENT_USER\\Software\\Classes"),
+    ("DEFAULT", "HKEY_USERS\\.DEFAULT"),
+];
+
+/// Describes which files make up a hive and how they should be parsed.
+#[derive(Debug, Clone, Default)]
+pub struct HiveSource {
+    pub path: PathBuf,
+    pub transaction_logs: Vec<PathBuf>,
+    pub recover_deleted: bool,
+    /// Key the hive's root stands for when exporting, such as `HKEY_LOCAL_MACHINE\SOFTWARE`.
+    pub mount_point: String,
+}
+
+impl From<&HiveArgs> for HiveSource {
+    fn from(cli: &HiveArgs) -> Self {
+        let mut transaction_logs = cli.logs.clone();
+        if !cli.no_log_discovery {
+            for log in discover_transaction_logs(&cli.hive) {
+                if !transaction_logs.contains(&log) {
+                    transaction_logs.push(log);
+                }
+            }
+        }
+
+        let mount_point = cli
+            .mount_point
+            .clone()
+            .unwrap_or_else(|| default_mount_point(&cli.hive));
+
+        HiveSource {
+            path: cli.hive.clone(),
+            transaction_logs,
+            recover_deleted: cli.recover_deleted,
+            mount_point: mount_point.trim_end_matches('\\').to_string(),
+        }
+    }
+}
+
+impl HiveSource {
+    /// Checks that the files exist and that the primary file looks like a hive.
+    pub fn check(&self) -> AppResult<()> {
+        check_signature(&self.path)?;
+        for log in &self.transaction_logs {
+            if !log.is_file() {
+                return Err(format!("transaction log {} does not exist", log.display()).into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the files, see [`HiveSource::check`], then builds a [`Parser`] for them.
+    pub fn open(&self) -> AppResult<Parser> {
+        self.check()?;
+        let mut builder = ParserBuilder::from_path(self.path.clone());
+        builder.recover_deleted(self.recover_deleted);
+        for log in &self.transaction_logs {
+            builder.with_transaction_log(log.clone());
+        }
+        builder
+            .build()
+            .map_err(|e| format!("failed to parse {}: {}", self.path.display(), e).into())
+    }
+}
+
+/// Start of the parse log notatin writes when the primary file was not written out cleanly.
+const RECOVERY_REQUIRED: &str = "Hive requires recovery: ";
+/// Start of the parse log notatin writes after replaying transaction logs.
+const LOGS_APPLIED: &str = "Applied transaction log(s). Sequence numbers have been updated to ";
+
+/// Outcome of replaying transaction logs over the primary hive file, as notatin
+/// reported it in the parse logs.
+#[derive(Debug, Clone, Default)]
+pub struct LogReplay {
+    /// Transaction logs handed to the parser.
+    pub logs: Vec<PathBuf>,
+    /// The primary file's sequence numbers or checksum are off, i.e. it was not written out cleanly.
+    pub dirty: bool,
+    /// Secondary sequence number of the primary file, the first one the logs have to provide.
+    pub first_sequence_number: Option<u32>,
+    /// Sequence number of the last log entry notatin applied, if it applied any.
+    pub last_sequence_number: Option<u32>,
+    /// Log files and entries notatin skipped, in its own words.
+    pub skipped: Vec<String>,
+}
+
+impl LogReplay {
+    /// Reads what notatin did with the transaction logs of `source` while building `parser`.
+    pub fn from_parser(source: &HiveSource, parser: &Parser) -> LogReplay {
+        let messages = parser.get_parse_logs().get().into_iter().flatten();
+        LogReplay::from_messages(
+            &source.transaction_logs,
+            messages.map(|log| log.text.as_str()),
+        )
+    }
+
+    fn from_messages<'a>(
+        logs: &[PathBuf],
+        messages: impl IntoIterator<Item = &'a str>,
+    ) -> LogReplay {
+        let mut replay = LogReplay {
+            logs: logs.to_vec(),
+            ..LogReplay::default()
+        };
+        for message in messages {
+            if let Some(reason) = message.strip_prefix(RECOVERY_REQUIRED) {
+                // "primary and secondary sequence numbers do not match. <primary>, <secondary>"
+                replay.dirty = true;
+                if let Some((_, secondary)) = reason.rsplit_once(", ") {
+                    replay.first_sequence_number = secondary.trim().parse().ok();
+                }
+            } else if let Some(rest) = message.strip_prefix(LOGS_APPLIED) {
+                replay.last_sequence_number = rest
+                    .split_once('.')
+                    .and_then(|(number, _)| number.parse().ok());
+            } else if message.starts_with("Skipping log ") || message.starts_with("Stopping log ") {
+                replay.skipped.push(message.to_string());
+            }
+        }
+        replay
+    }
+
+    pub fn applied(&self) -> bool {
+        self.last_sequence_number.is_some()
+    }
+
+    /// Number of log entries (one per sequence number) that were applied.
+    pub fn recovered_sequence_numbers(&self) -> u32 {
+        match (self.first_sequence_number, self.last_sequence_number) {
+            (Some(first), Some(last)) if last >= first => last - first + 1,
+            _ => 0,
+        }
+    }
+}
+
+/// Key Windows loads the hive at `path` under, going by its file name.
+pub fn default_mount_point(path: &Path) -> String {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    USER_MOUNT_POINTS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(&stem))
+        .map(|(_, mount_point)| mount_point.to_string())
+        .unwrap_or_else(|| format!("HKEY_LOCAL_MACHINE\\{}", stem.to_uppercase()))
+}
+
+/// Finds `<hive>.LOG1` and `<hive>.LOG2` next to the hive, in either case.
+pub fn discover_transaction_logs(hive: &Path) -> Vec<PathBuf> {
+    ["LOG1", "LOG2"]
+        .iter()
+        .filter_map(|extension| {
+            [extension.to_string(), extension.to_lowercase()]
+                .into_iter()
+                .map(|extension| {
+                    let mut path = OsString::from(hive.as_os_str());
+                    path.push(".");
+                    path.push(extension);
+                    PathBuf::from(path)
+                })
+                .find(|path| path.is_file())
+        })
+        .collect()
+}
+
+fn check_signature(path: &Path) -> AppResult<()> {
+    let mut signature = [0u8; 4];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut signature))
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+
+    if &signature != REGF_SIGNATURE {
+        return Err(format!(
+            "{} is not a registry hive (missing \"regf\" signature)",
+            path.display()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Decodes little-endian UTF-16, stopping at the first NUL.
+pub fn utf16_string(data: &[u8]) -> String {
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .map(|bytes| u16::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+pub(crate) fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    data.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mount_points() {
+        let mount_point = |path: &str| default_mount_point(Path::new(path));
+        assert_eq!(mount_point("C/Users/a/NTUSER.DAT"), "HKEY_CURRENT_USER");
+        assert_eq!(
+            mount_point("usrclass.dat"),
+            "HKEY_CURRENT_USER\\Software\\Classes"
+        );
+        assert_eq!(
+            mount_point("config/Software"),
+            "HKEY_LOCAL_MACHINE\\SOFTWARE"
+        );
+        assert_eq!(mount_point("SYSTEM"), "HKEY_LOCAL_MACHINE\\SYSTEM");
+    }
+
+    #[test]
+    fn replay_from_parse_logs() {
+        let logs = [PathBuf::from("SYSTEM.LOG1"), PathBuf::from("SYSTEM.LOG2")];
+        let replay = LogReplay::from_messages(
+            &logs,
+            [
+                "Hive requires recovery: primary and secondary sequence numbers do not match. 4019, 4018",
+                "Skipping log entry; the log entry sequence number (4010) is less than to the primary file's secondary sequence number (4018)",
+                "Applied transaction log(s). Sequence numbers have been updated to 4066. New Checksum: 0x1234ABCD",
+            ],
+        );
+        assert!(replay.dirty && replay.applied());
+        assert_eq!(replay.recovered_sequence_numbers(), 49);
+        assert_eq!(replay.skipped.len(), 1);
+
+        let replay = LogReplay::from_messages(
+            &logs,
+            ["Skipping transaction logs because the primary file's primary_sequence_number matches the secondary_sequence_number"],
+        );
+        assert!(!replay.dirty && !replay.applied() && replay.skipped.is_empty());
+    }
+}
@@ -0,0 +1,461 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use winstructs::security as raw;
+
+/// Security descriptor control flags that SDDL cares about.
+const SE_DACL_AUTO_INHERIT_REQ: u16 = 0x0100;
+const SE_SACL_AUTO_INHERIT_REQ: u16 = 0x0200;
+const SE_DACL_AUTO_INHERITED: u16 = 0x0400;
+const SE_SACL_AUTO_INHERITED: u16 = 0x0800;
+const SE_DACL_PROTECTED: u16 = 0x1000;
+const SE_SACL_PROTECTED: u16 = 0x2000;
+
+/// Access mask bits, as `(SDDL code, description, mask)`, in SDDL order.
+const ACCESS_RIGHTS: &[(&str, &str, u32)] = &[
+    ("GA", "Generic All", 0x1000_0000),
+    ("GR", "Generic Read", 0x8000_0000),
+    ("GW", "Generic Write", 0x4000_0000),
+    ("GX", "Generic Execute", 0x2000_0000),
+    ("RC", "Read Control", 0x0002_0000),
+    ("SD", "Delete", 0x0001_0000),
+    ("WD", "Write DAC", 0x0004_0000),
+    ("WO", "Write Owner", 0x0008_0000),
+    ("CC", "Query Value", 0x0000_0001),
+    ("DC", "Set Value", 0x0000_0002),
+    ("LC", "Create Subkey", 0x0000_0004),
+    ("SW", "Enumerate Subkeys", 0x0000_0008),
+    ("RP", "Notify", 0x0000_0010),
+    ("WP", "Create Link", 0x0000_0020),
+];
+
+/// Combined registry access masks with their own SDDL code.
+const KEY_RIGHTS: &[(&str, &str, u32)] = &[
+    ("KA", "Full Control", 0x000F_003F),
+    ("KR", "Read", 0x0002_0019),
+    ("KW", "Write", 0x0002_0006),
+];
+
+/// ACE flags, as `(SDDL code, mask)`, in SDDL order.
+const ACE_FLAGS: &[(&str, u8)] = &[
+    ("OI", 0x01),
+    ("CI", 0x02),
+    ("NP", 0x04),
+    ("IO", 0x08),
+    ("ID", 0x10),
+    ("SA", 0x40),
+    ("FA", 0x80),
+];
+
+/// Well-known SIDs as `(SID, SDDL alias, account name)`.
+const WELL_KNOWN_SIDS: &[(&str, &str, &str)] = &[
+    ("S-1-0-0", "", "NULL SID"),
+    ("S-1-1-0", "WD", "Everyone"),
+    ("S-1-2-0", "", "LOCAL"),
+    ("S-1-3-0", "CO", "CREATOR OWNER"),
+    ("S-1-3-1", "CG", "CREATOR GROUP"),
+    ("S-1-3-4", "OW", "OWNER RIGHTS"),
+    ("S-1-5-2", "NU", "NT AUTHORITY\\NETWORK"),
+    ("S-1-5-4", "IU", "NT AUTHORITY\\INTERACTIVE"),
+    ("S-1-5-6", "SU", "NT AUTHORITY\\SERVICE"),
+    ("S-1-5-7", "AN", "NT AUTHORITY\\ANONYMOUS LOGON"),
+    (
+        "S-1-5-9",
+        "ED",
+        "NT AUTHORITY\\ENTERPRISE DOMAIN CONTROLLERS",
+    ),
+    ("S-1-5-10", "PS", "NT AUTHORITY\\SELF"),
+    ("S-1-5-11", "AU", "NT AUTHORITY\\Authenticated Users"),
+    ("S-1-5-12", "RC", "NT AUTHORITY\\RESTRICTED"),
+    ("S-1-5-18", "SY", "NT AUTHORITY\\SYSTEM"),
+    ("S-1-5-19", "LS", "NT AUTHORITY\\LOCAL SERVICE"),
+    ("S-1-5-20", "NS", "NT AUTHORITY\\NETWORK SERVICE"),
+    ("S-1-5-32-544", "BA", "BUILTIN\\Administrators"),
+    ("S-1-5-32-545", "BU", "BUILTIN\\Users"),
+    ("S-1-5-32-546", "BG", "BUILTIN\\Guests"),
+    ("S-1-5-32-547", "PU", "BUILTIN\\Power Users"),
+    ("S-1-5-32-548", "AO", "BUILTIN\\Account Operators"),
+    ("S-1-5-32-549", "SO", "BUILTIN\\Server Operators"),
+    ("S-1-5-32-550", "PO", "BUILTIN\\Print Operators"),
+    ("S-1-5-32-551", "BO", "BUILTIN\\Backup Operators"),
+    ("S-1-5-32-552", "RE", "BUILTIN\\Replicator"),
+    ("S-1-5-32-555", "RD", "BUILTIN\\Remote Desktop Users"),
+    (
+        "S-1-5-32-556",
+        "NO",
+        "BUILTIN\\Network Configuration Operators",
+    ),
+    (
+        "S-1-5-80-956008885-3418522649-1831038044-1853292631-2271478464",
+        "",
+        "NT SERVICE\\TrustedInstaller",
+    ),
+    ("S-1-15-2-1", "AC", "ALL APPLICATION PACKAGES"),
+    ("S-1-15-2-2", "", "ALL RESTRICTED APPLICATION PACKAGES"),
+    ("S-1-16-4096", "LW", "Mandatory Label\\Low Mandatory Level"),
+    (
+        "S-1-16-8192",
+        "ME",
+        "Mandatory Label\\Medium Mandatory Level",
+    ),
+    (
+        "S-1-16-12288",
+        "HI",
+        "Mandatory Label\\High Mandatory Level",
+    ),
+    (
+        "S-1-16-16384",
+        "SI",
+        "Mandatory Label\\System Mandatory Level",
+    ),
+];
+
+/// Well-known relative identifiers of domain and machine accounts.
+const WELL_KNOWN_RIDS: &[(u32, &str)] = &[
+    (500, "Administrator"),
+    (501, "Guest"),
+    (512, "Domain Admins"),
+    (513, "Domain Users"),
+    (514, "Domain Guests"),
+    (515, "Domain Computers"),
+    (516, "Domain Controllers"),
+    (518, "Schema Admins"),
+    (519, "Enterprise Admins"),
+];
+
+/// A security identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sid {
+    pub revision: u8,
+    pub authority: u64,
+    pub sub_authorities: Vec<u32>,
+}
+
+impl Sid {
+    /// Name of the account behind a well-known SID.
+    pub fn account_name(&self) -> Option<String> {
+        let sid = self.to_string();
+        if let Some((_, _, name)) = WELL_KNOWN_SIDS.iter().find(|(known, _, _)| *known == sid) {
+            return Some(name.to_string());
+        }
+        if self.is_domain_relative() {
+            let rid = self.sub_authorities.last()?;
+            return WELL_KNOWN_RIDS
+                .iter()
+                .find(|(known, _)| known == rid)
+                .map(|(_, name)| name.to_string());
+        }
+        if sid.starts_with("S-1-5-80-") {
+            return Some("NT SERVICE\\<service>".to_string());
+        }
+        None
+    }
+
+    /// The account name followed by the SID, or just the SID for unknown accounts.
+    pub fn describe(&self) -> String {
+        match self.account_name() {
+            Some(name) => format!("{} ({})", name, self),
+            None => self.to_string(),
+        }
+    }
+
+    /// The two letter alias SDDL uses for this SID, or the SID itself.
+    pub fn to_sddl(&self) -> String {
+        let sid = self.to_string();
+        let alias = WELL_KNOWN_SIDS
+            .iter()
+            .find(|(known, alias, _)| *known == sid && !alias.is_empty())
+            .map(|(_, alias, _)| *alias);
+        alias.map(str::to_string).unwrap_or(sid)
+    }
+
+    fn is_domain_relative(&self) -> bool {
+        self.authority == 5 && self.sub_authorities.len() == 5 && self.sub_authorities[0] == 21
+    }
+}
+
+/// Reads a SID in its `S-R-I-S…` string form, which is all winstructs exposes of one.
+impl FromStr for Sid {
+    type Err = ();
+
+    fn from_str(sid: &str) -> Result<Self, Self::Err> {
+        let mut parts = sid.strip_prefix("S-").ok_or(())?.split('-');
+        let revision = parts.next().and_then(|part| part.parse().ok()).ok_or(())?;
+        let authority = match parts.next().ok_or(())? {
+            hex if hex.starts_with("0x") => u64::from_str_radix(&hex[2..], 16).map_err(|_| ())?,
+            decimal => decimal.parse().map_err(|_| ())?,
+        };
+        let sub_authorities = parts
+            .map(|part| part.parse().map_err(|_| ()))
+            .collect::<Result<Vec<u32>, ()>>()?;
+        Ok(Sid {
+            revision,
+            authority,
+            sub_authorities,
+        })
+    }
+}
+
+impl Display for Sid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "S-{}-", self.revision)?;
+        if self.authority < 1 << 32 {
+            write!(f, "{}", self.authority)?;
+        } else {
+            write!(f, "{:#014x}", self.authority)?;
+        }
+        for sub_authority in &self.sub_authorities {
+            write!(f, "-{}", sub_authority)?;
+        }
+        Ok(())
+    }
+}
+
+/// An access control entry.
+#[derive(Debug, Clone)]
+pub struct Ace {
+    pub ace_type: u8,
+    pub flags: u8,
+    pub mask: u32,
+    pub sid: Option<Sid>,
+}
+
+impl Ace {
+    /// SDDL code of the ACE type.
+    pub fn type_code(&self) -> String {
+        match self.ace_type {
+            0x00 => "A".to_string(),
+            0x01 => "D".to_string(),
+            0x02 => "AU".to_string(),
+            0x03 => "AL".to_string(),
+            0x05 => "OA".to_string(),
+            0x06 => "OD".to_string(),
+            0x07 => "OU".to_string(),
+            0x08 => "OL".to_string(),
+            0x09 => "XA".to_string(),
+            0x0A => "XD".to_string(),
+            0x0D => "XU".to_string(),
+            0x11 => "ML".to_string(),
+            0x12 => "RA".to_string(),
+            0x13 => "SP".to_string(),
+            other => format!("{:#04x}", other),
+        }
+    }
+
+    /// Human readable ACE type.
+    pub fn type_name(&self) -> &'static str {
+        match self.ace_type {
+            0x00 | 0x05 | 0x09 | 0x0B => "Allow",
+            0x01 | 0x06 | 0x0A | 0x0C => "Deny",
+            0x02 | 0x07 | 0x0D | 0x0F => "Audit",
+            0x03 | 0x08 | 0x10 => "Alarm",
+            0x11 => "Label",
+            0x12 => "Attribute",
+            0x13 => "Policy",
+            _ => "Unknown",
+        }
+    }
+
+    /// SDDL codes of the ACE flags.
+    pub fn flags_code(&self) -> String {
+        ACE_FLAGS
+            .iter()
+            .filter(|(_, flag)| self.flags & flag != 0)
+            .map(|(code, _)| *code)
+            .collect()
+    }
+
+    /// SDDL representation of the access mask.
+    pub fn rights_code(&self) -> String {
+        if let Some((code, _, _)) = KEY_RIGHTS.iter().find(|(_, _, mask)| *mask == self.mask) {
+            return code.to_string();
+        }
+        let known = ACCESS_RIGHTS.iter().fold(0, |acc, (_, _, mask)| acc | mask);
+        if self.mask & !known != 0 || self.mask == 0 {
+            return format!("{:#x}", self.mask);
+        }
+        ACCESS_RIGHTS
+            .iter()
+            .filter(|(_, _, mask)| self.mask & mask != 0)
+            .map(|(code, _, _)| *code)
+            .collect()
+    }
+
+    /// Human readable access rights.
+    pub fn rights_description(&self) -> String {
+        if let Some((_, name, _)) = KEY_RIGHTS.iter().find(|(_, _, mask)| *mask == self.mask) {
+            return name.to_string();
+        }
+        let mut rights: Vec<String> = ACCESS_RIGHTS
+            .iter()
+            .filter(|(_, _, mask)| self.mask & mask != 0)
+            .map(|(_, name, _)| name.to_string())
+            .collect();
+        let known = ACCESS_RIGHTS.iter().fold(0, |acc, (_, _, mask)| acc | mask);
+        if self.mask & !known != 0 {
+            rights.push(format!("{:#x}", self.mask & !known));
+        }
+        rights.join(", ")
+    }
+
+    pub fn to_sddl(&self) -> String {
+        format!(
+            "({};{};{};;;{})",
+            self.type_code(),
+            self.flags_code(),
+            self.rights_code(),
+            self.sid.as_ref().map(Sid::to_sddl).unwrap_or_default()
+        )
+    }
+}
+
+impl From<&raw::Ace> for Ace {
+    fn from(ace: &raw::Ace) -> Self {
+        let (mask, sid) = match &ace.data {
+            raw::AceData::Basic(data) => (data.access_rights, Some(&data.sid)),
+            raw::AceData::Object(data) => (data.access_rights, Some(&data.sid)),
+            raw::AceData::Unhandled(_) => (0, None),
+        };
+        Ace {
+            ace_type: ace.ace_type.clone() as u8,
+            flags: ace.ace_flags.bits(),
+            mask,
+            sid: sid.and_then(|sid| sid.to_string().parse().ok()),
+        }
+    }
+}
+
+/// A security descriptor as notatin reads it from a key security (`sk`) cell.
+#[derive(Debug, Clone, Default)]
+pub struct SecurityDescriptor {
+    pub control: u16,
+    pub owner: Option<Sid>,
+    pub group: Option<Sid>,
+    pub dacl: Option<Vec<Ace>>,
+    pub sacl: Option<Vec<Ace>>,
+}
+
+impl SecurityDescriptor {
+    pub fn to_sddl(&self) -> String {
+        let mut sddl = String::new();
+        if let Some(owner) = &self.owner {
+            sddl.push_str(&format!("O:{}", owner.to_sddl()));
+        }
+        if let Some(group) = &self.group {
+            sddl.push_str(&format!("G:{}", group.to_sddl()));
+        }
+        if let Some(dacl) = &self.dacl {
+            sddl.push_str("D:");
+            sddl.push_str(&acl_flags(
+                self.control,
+                SE_DACL_PROTECTED,
+                SE_DACL_AUTO_INHERIT_REQ,
+                SE_DACL_AUTO_INHERITED,
+            ));
+            dacl.iter().for_each(|ace| sddl.push_str(&ace.to_sddl()));
+        }
+        if let Some(sacl) = &self.sacl {
+            sddl.push_str("S:");
+            sddl.push_str(&acl_flags(
+                self.control,
+                SE_SACL_PROTECTED,
+                SE_SACL_AUTO_INHERIT_REQ,
+                SE_SACL_AUTO_INHERITED,
+            ));
+            sacl.iter().for_each(|ace| sddl.push_str(&ace.to_sddl()));
+        }
+        sddl
+    }
+}
+
+/// winstructs drops the control and ACE flags it does not know, such as
+/// `SE_DACL_PROTECTED` and `INHERITED_ACE`, so those never show up.
+impl From<&raw::SecurityDescriptor> for SecurityDescriptor {
+    fn from(descriptor: &raw::SecurityDescriptor) -> Self {
+        let acl = |acl: &Option<raw::Acl>| {
+            acl.as_ref()
+                .map(|acl| acl.entries.iter().map(Ace::from).collect())
+        };
+        SecurityDescriptor {
+            control: descriptor.header.control_flags.bits(),
+            owner: descriptor.owner_sid.to_string().parse().ok(),
+            group: descriptor.group_sid.to_string().parse().ok(),
+            dacl: acl(&descriptor.dacl),
+            sacl: acl(&descriptor.sacl),
+        }
+    }
+}
+
+fn acl_flags(control: u16, protected: u16, auto_inherit_req: u16, auto_inherited: u16) -> String {
+    [
+        ("P", protected),
+        ("AR", auto_inherit_req),
+        ("AI", auto_inherited),
+    ]
+    .iter()
+    .filter(|(_, flag)| control & flag != 0)
+    .map(|(code, _)| *code)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Self-relative descriptor with a protected, auto-inherited DACL of three ACEs.
+    const DESCRIPTOR: [u8; 124] = [
+        0x01, 0x00, 0x04, 0x94, 0x60, 0x00, 0x00, 0x00, 0x70, 0x00, 0x00, 0x00, //
+        0x00, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x02, 0x00, 0x4C, 0x00, //
+        0x03, 0x00, 0x00, 0x00, 0x00, 0x02, 0x18, 0x00, 0x3F, 0x00, 0x0F, 0x00, //
+        0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x20, 0x00, 0x00, 0x00, //
+        0x20, 0x02, 0x00, 0x00, 0x00, 0x03, 0x18, 0x00, 0x19, 0x00, 0x02, 0x00, //
+        0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x20, 0x00, 0x00, 0x00, //
+        0x21, 0x02, 0x00, 0x00, 0x00, 0x0A, 0x14, 0x00, 0x00, 0x00, 0x00, 0x10, //
+        0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, //
+        0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x20, 0x00, 0x00, 0x00, //
+        0x20, 0x02, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, //
+        0x12, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn formats_descriptor() {
+        let raw = raw::SecurityDescriptor::from_stream(&mut Cursor::new(&DESCRIPTOR[..])).unwrap();
+        let descriptor = SecurityDescriptor::from(&raw);
+        // winstructs drops SE_DACL_PROTECTED, hence no P.
+        assert_eq!(
+            descriptor.to_sddl(),
+            "O:BAG:SYD:AI(A;CI;KA;;;BA)(A;OICI;KR;;;BU)(A;CIIO;GA;;;CO)"
+        );
+        let owner = descriptor.owner.unwrap();
+        assert_eq!(owner.describe(), "BUILTIN\\Administrators (S-1-5-32-544)");
+
+        let dacl = descriptor.dacl.unwrap();
+        assert_eq!(dacl[1].type_name(), "Allow");
+        assert_eq!(dacl[1].rights_description(), "Read");
+        assert_eq!(dacl[2].rights_description(), "Generic All");
+        assert!(descriptor.sacl.is_none());
+    }
+
+    #[test]
+    fn sid_strings() {
+        for sid in ["S-1-5-18", "S-1-5-21-1-2-3-500", "S-1-0x123456789abc-1"] {
+            assert_eq!(sid.parse::<Sid>().unwrap().to_string(), sid);
+        }
+        assert!("S-1".parse::<Sid>().is_err());
+        assert!("X-1-5".parse::<Sid>().is_err());
+    }
+
+    #[test]
+    fn unknown_rights_and_sids() {
+        let ace = Ace {
+            ace_type: 0x01,
+            flags: 0x10,
+            mask: 0x0000_0101,
+            sid: "S-1-5-21-7".parse().ok(),
+        };
+        assert_eq!(ace.to_sddl(), "(D;ID;0x101;;;S-1-5-21-7)");
+        assert_eq!(ace.rights_description(), "Query Value, 0x100");
+    }
+}
@@ -0,0 +1,60 @@
+use std::ops::ControlFlow;
+
+use notatin::cell_key_node::CellKeyNode;
+use notatin::parser::Parser;
+
+/// Visits `key` and all of its descendants depth-first, parents before children.
+///
+/// `path` is the path of `key` below the root key; `visit` receives each key
+/// along with its own path and can stop the walk by returning [`ControlFlow::Break`].
+/// Recovered deleted subkeys are only visited if `include_deleted` is set.
+pub fn walk_keys<F>(
+    parser: &mut Parser,
+    key: CellKeyNode,
+    path: String,
+    include_deleted: bool,
+    mut visit: F,
+) -> ControlFlow<()>
+where
+    F: FnMut(&CellKeyNode, &str) -> ControlFlow<()>,
+{
+    let mut stack = vec![(key, path)];
+    while let Some((mut key, path)) = stack.pop() {
+        visit(&key, &path)?;
+
+        let mut subkeys = key.read_sub_keys(parser);
+        if include_deleted {
+            subkeys.extend(key.deleted_keys.iter().cloned());
+        }
+        stack.extend(subkeys.into_iter().rev().map(|subkey| {
+            let subkey_path = child_path(&path, &subkey.key_name);
+            (subkey, subkey_path)
+        }));
+    }
+    ControlFlow::Continue(())
+}
+
+/// Joins a key path below the root key with the name of one of its subkeys.
+/// Also joins a mount point with a path below the root key, which is empty
+/// for the root key itself.
+pub fn child_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else if name.is_empty() {
+        parent.to_string()
+    } else {
+        format!("{}\\{}", parent, name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_paths() {
+        assert_eq!(child_path("", "Software"), "Software");
+        assert_eq!(child_path("Software", "Classes"), "Software\\Classes");
+        assert_eq!(child_path("HKEY_CURRENT_USER", ""), "HKEY_CURRENT_USER");
+    }
+}
@@ -15,3 +15,18 @@ pub mod tui;
 
 /// Event handler.
 pub mod handler;
+
+/// Command-line interface.
+pub mod cli;
+
+/// Opening hive files.
+pub mod hive;
+
+/// Writing keys and values out in other formats.
+pub mod export;
+
+/// Per-user configuration files.
+pub mod config;
+
+/// Interpreting well-known value layouts.
+pub mod decoders;
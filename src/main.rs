@@ -1,19 +1,129 @@
+use clap::Parser as _;
+use hiview::app::navigation::Navigation;
 use hiview::app::{App, AppResult};
+use hiview::cli::{Cli, Command, ExportArgs, HiveArgs, OutputMode};
+use hiview::decoders::timestamp;
 use hiview::event::{Event, EventHandler};
-use hiview::handler::handle_key_events;
+use hiview::export::{export, ExportFormat, ExportScope};
+use hiview::handler::{handle_key_events, handle_mouse_events};
+use hiview::hive::HiveSource;
 use hiview::tui::Tui;
-use notatin::parser_builder::ParserBuilder;
+use hiview::widgets::value_selector::ValueCellPreview;
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
-use std::env::args;
-use std::io;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() -> AppResult<()> {
+async fn main() -> ExitCode {
+    // Usage errors are reported by clap, which exits with status 2.
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Some(Command::Export(args)) => run_export(args),
+        None => run(cli).await,
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("hiview: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> AppResult<()> {
     // Create an application.
-    let parser = ParserBuilder::from_path(args().collect::<Vec<String>>()[1].clone()).build();
-    let mut app = App::new(parser.expect("The hive to parse correctly"));
+    let hive = cli.hive.as_ref().ok_or("no hive file given")?;
+    let source = HiveSource::from(hive);
+    let parser = source.open()?;
+    let mut app = App::new(parser, source)?;
+    app.state.timezone = cli.timezone;
+
+    if let Some(baseline) = &cli.compare {
+        let baseline = HiveSource::from(&HiveArgs {
+            hive: baseline.clone(),
+            logs: vec![],
+            no_log_discovery: hive.no_log_discovery,
+            recover_deleted: false,
+            mount_point: None,
+        });
+        baseline.check()?;
+        app.state
+            .navigation
+            .compare_with(&app.state.source, baseline);
+    }
+
+    if let Some(path) = &cli.key {
+        let (key, resolved) = app
+            .state
+            .navigation
+            .resolve_path(path)
+            .ok_or_else(|| format!("key {} not found in {}", path, hive.hive.display()))?;
+        // The interface shows the key selected in its parent so that its values
+        // are visible; the text output lists the key's own contents.
+        match cli.mode {
+            OutputMode::Tui => {
+                app.state.navigation.reveal(&resolved, None);
+            }
+            OutputMode::Text => app.state.navigation.select_key(key),
+        }
+    }
+
+    match cli.mode {
+        OutputMode::Tui => run_tui(app).await,
+        OutputMode::Text => print_current_key(&app, &mut io::stdout().lock()),
+    }
+}
+
+/// Writes a key subtree to a file or standard output without starting the interface.
+fn run_export(args: ExportArgs) -> AppResult<()> {
+    let format = match (args.format, &args.output) {
+        (Some(format), _) => format,
+        (None, Some(file)) => ExportFormat::from_path(file).ok_or_else(|| {
+            format!(
+                "cannot tell the format of {}, pass --format",
+                file.display()
+            )
+        })?,
+        (None, None) => ExportFormat::Jsonl,
+    };
 
+    let source = HiveSource::from(&args.hive);
+    let mut navigation = Navigation::new(source.open()?, source.recover_deleted);
+    let (key, path) = match &args.key {
+        Some(path) => navigation
+            .resolve_path(path)
+            .ok_or_else(|| format!("key {} not found in {}", path, args.hive.hive.display()))?,
+        None => (navigation.current_key.clone(), String::new()),
+    };
+    let scope = ExportScope {
+        path: &path,
+        mount_point: &source.mount_point,
+        include_deleted: source.recover_deleted,
+    };
+
+    match &args.output {
+        Some(file) => {
+            let mut out = BufWriter::new(File::create(file)?);
+            export(format, &mut navigation.parser, key, &scope, &mut out)?;
+            out.flush()?;
+        }
+        None => {
+            export(
+                format,
+                &mut navigation.parser,
+                key,
+                &scope,
+                &mut io::stdout().lock(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+async fn run_tui(mut app: App) -> AppResult<()> {
     // Initialize the terminal user interface.
     let backend = CrosstermBackend::new(io::stderr());
     let terminal = Terminal::new(backend)?;
@@ -29,7 +139,7 @@ async fn main() -> AppResult<()> {
         match tui.events.next().await? {
             Event::Tick => app.tick(),
             Event::Key(key_event) => handle_key_events(key_event, &mut app)?,
-            Event::Mouse(_) => {}
+            Event::Mouse(mouse_event) => handle_mouse_events(mouse_event, &mut app)?,
             Event::Resize(_, _) => {}
         }
     }
@@ -38,3 +148,28 @@ async fn main() -> AppResult<()> {
     tui.exit()?;
     Ok(())
 }
+
+/// Writes the subkeys and values of the current key as plain text.
+fn print_current_key(app: &App, out: &mut impl Write) -> AppResult<()> {
+    let navigation = &app.state.navigation;
+
+    writeln!(out, "[{}]", navigation.current_key.key_name)?;
+    for subkey in &navigation.current_subkeys {
+        writeln!(out, "  {}\\", subkey.key_name)?;
+    }
+    let path = navigation.current_path();
+    for value in navigation.current_key.value_iter() {
+        let content = value.get_content().0;
+        write!(
+            out,
+            "  {} = {}",
+            value.get_pretty_name(),
+            ValueCellPreview(value.data_type, content.clone())
+        )?;
+        match timestamp::detect(&path, &value.detail.value_name(), &content) {
+            Some(time) => writeln!(out, " ({})", app.state.timezone.format(time.time))?,
+            None => writeln!(out)?,
+        }
+    }
+    Ok(())
+}
@@ -1,11 +1,12 @@
 use crate::app::{App, AppResult};
 use crate::event::EventHandler;
 use crate::ui;
+use base64::Engine;
 use crossterm::event::{DisableMouseCapture, EnableMouseCapture};
 use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::backend::Backend;
 use ratatui::Terminal;
-use std::io;
+use std::io::{self, Write};
 use std::panic;
 
 /// Representation of a terminal user interface.
@@ -74,3 +75,13 @@ impl<B: Backend> Tui<B> {
         Ok(())
     }
 }
+
+/// Puts `text` on the system clipboard through the terminal, using an OSC 52
+/// escape sequence. This also works over SSH, if the terminal allows it.
+pub fn copy_to_clipboard(text: &str) -> AppResult<()> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut stderr = io::stderr();
+    write!(stderr, "\x1b]52;c;{}\x07", encoded)?;
+    stderr.flush()?;
+    Ok(())
+}
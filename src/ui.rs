@@ -10,6 +10,6 @@ pub fn render(_app: &mut App, frame: &mut Frame) {
     // See the following resources:
     // - https://docs.rs/ratatui/latest/ratatui/widgets/index.html
     // - https://github.com/ratatui-org/ratatui/tree/master/examples
-    let main_widget = MainWidget{};
+    let main_widget = MainWidget {};
     frame.render_stateful_widget(main_widget, frame.size(), &mut _app.state);
 }
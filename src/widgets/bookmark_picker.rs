@@ -0,0 +1,96 @@
+use crate::app::keymap::{Action, Context};
+use crate::app::state::State;
+use crate::widgets::help::hints;
+use ratatui::text::Text;
+use ratatui::widgets::StatefulWidget;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::*,
+    symbols::border,
+    widgets::{block::*, *},
+};
+
+/// Popup listing the bookmarks, with the ones missing from this hive dimmed.
+pub struct BookmarkPicker;
+
+impl StatefulWidget for &mut BookmarkPicker {
+    type State = State;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut State)
+    where
+        Self: Sized,
+    {
+        let [_, area, _] = Layout::vertical([
+            Constraint::Percentage(20),
+            Constraint::Percentage(60),
+            Constraint::Percentage(20),
+        ])
+        .areas(area);
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Percentage(15),
+            Constraint::Percentage(70),
+            Constraint::Percentage(15),
+        ])
+        .areas(area);
+
+        let bookmarks = &mut state.bookmarks;
+        let instructions = Title::from(hints(
+            &state.keymap,
+            Context::Bookmarks,
+            &[
+                ("Open", Action::Open),
+                ("Remove", Action::Remove),
+                ("Close", Action::Close),
+                ("Keys", Action::Help),
+            ],
+        ));
+        let block = Block::default()
+            .title(Title::from("bookmarks").alignment(Alignment::Center))
+            .title(
+                instructions
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
+            .borders(Borders::ALL)
+            .border_set(border::THICK)
+            .border_style(Color::Green);
+
+        Clear.render(area, buf);
+        if bookmarks.paths.is_empty() {
+            let add = state
+                .keymap
+                .first_key(Context::Global, Action::ToggleBookmark)
+                .map(|key| format!(", add one with <{}>", key))
+                .unwrap_or_default();
+            Paragraph::new(format!("no bookmarks yet{}", add))
+                .block(block)
+                .render(area, buf);
+            return;
+        }
+
+        let rows: Vec<Row> = bookmarks
+            .paths
+            .iter()
+            .zip(&bookmarks.present)
+            .map(|(path, present)| {
+                let label = if path.is_empty() { "\\" } else { path };
+                let row = Row::new(vec![
+                    Cell::new(label.to_string()),
+                    Cell::new(if *present { "" } else { "[not in this hive]" }),
+                ]);
+                if *present {
+                    row
+                } else {
+                    row.dark_gray()
+                }
+            })
+            .collect();
+
+        let table = Table::new(rows, vec![Constraint::Fill(1), Constraint::Length(18)])
+            .block(block)
+            .highlight_style(Style::new().add_modifier(Modifier::BOLD))
+            .highlight_symbol(Text::from("|").blue());
+
+        <Table as StatefulWidget>::render(table, area, buf, &mut bookmarks.table_state);
+    }
+}
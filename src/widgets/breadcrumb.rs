@@ -0,0 +1,153 @@
+use crate::app::keymap::{Action, Context};
+use crate::app::navigation::Navigation;
+use crate::app::state::State;
+use crate::hive::walk::child_path;
+use crate::widgets::help::hints;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::StatefulWidget;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::*,
+    widgets::*,
+};
+
+const SEPARATOR: &str = " \\ ";
+const ELLIPSIS: &str = "…";
+
+/// One component of the breadcrumb path.
+#[derive(Debug, Clone)]
+pub struct Segment {
+    pub label: String,
+    /// Path of the key below the root key.
+    pub path: String,
+    /// Whether this is the selected subkey rather than part of the current key's path.
+    pub selected: bool,
+}
+
+/// Breaks the location of `navigation` into segments, from the root key to the selected subkey.
+pub fn segments(navigation: &Navigation) -> Vec<Segment> {
+    let root = navigation
+        .current_key
+        .path
+        .trim_start_matches('\\')
+        .split('\\')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let mut segments = vec![Segment {
+        label: root,
+        path: String::new(),
+        selected: false,
+    }];
+
+    let current_path = navigation.current_path();
+    let mut path = String::new();
+    for name in current_path.split('\\').filter(|name| !name.is_empty()) {
+        path = child_path(&path, name);
+        segments.push(Segment {
+            label: name.to_string(),
+            path: path.clone(),
+            selected: false,
+        });
+    }
+
+    if let Some(subkey) = &navigation.selected_subkey {
+        segments.push(Segment {
+            label: subkey.key_name.clone(),
+            path: navigation.selected_path(),
+            selected: true,
+        });
+    }
+    segments
+}
+
+/// Drops segments after the root until the path fits in `width` columns,
+/// marking the gap with an ellipsis. The last segment is cut from the left
+/// if even that is not enough.
+pub fn fit_segments(mut segments: Vec<Segment>, width: usize) -> Vec<Segment> {
+    let length = |segments: &[Segment]| {
+        segments
+            .iter()
+            .map(|segment| segment.label.chars().count())
+            .sum::<usize>()
+            + SEPARATOR.len() * segments.len().saturating_sub(1)
+    };
+
+    let mut truncated = false;
+    while length(&segments) > width && segments.len() > 2 {
+        segments.remove(if truncated { 2 } else { 1 });
+        if !truncated {
+            segments.insert(
+                1,
+                Segment {
+                    label: ELLIPSIS.to_string(),
+                    path: String::new(),
+                    selected: false,
+                },
+            );
+            truncated = true;
+        }
+    }
+
+    let overflow = length(&segments).saturating_sub(width);
+    if overflow > 0 {
+        if let Some(last) = segments.last_mut() {
+            let keep = last.label.chars().count().saturating_sub(overflow + 1);
+            last.label = ELLIPSIS.to_string()
+                + &last
+                    .label
+                    .chars()
+                    .skip(last.label.chars().count() - keep)
+                    .collect::<String>();
+        }
+    }
+    segments
+}
+
+/// Header line showing where in the hive we are.
+pub struct Breadcrumb;
+
+impl StatefulWidget for &mut Breadcrumb {
+    type State = State;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut State)
+    where
+        Self: Sized,
+    {
+        let hint = hints(
+            &state.keymap,
+            Context::Global,
+            &[("copy", Action::CopyPath)],
+        );
+        let width = (area.width as usize).saturating_sub(hint.width() + 1);
+        let segments = fit_segments(segments(&state.navigation), width);
+
+        let mut spans: Vec<Span> = vec![" ".into()];
+        for (index, segment) in segments.iter().enumerate() {
+            if index > 0 {
+                spans.push(Span::from(SEPARATOR).dark_gray());
+            }
+            let span = if segment.selected {
+                Span::from(segment.label.clone()).green().bold()
+            } else {
+                Span::from(segment.label.clone()).bold()
+            };
+            // Only the root has an empty path, the ellipsis stands for no key.
+            if index == 0 || !segment.path.is_empty() {
+                let x = area.x + spans.iter().map(Span::width).sum::<usize>() as u16;
+                let clickable = Rect::new(x, area.y, span.width() as u16, 1).intersection(area);
+                state
+                    .mouse
+                    .areas
+                    .breadcrumb
+                    .push((clickable, segment.path.clone()));
+            }
+            spans.push(span);
+        }
+
+        Paragraph::new(Line::from(spans)).render(area, buf);
+        Paragraph::new(hint)
+            .alignment(Alignment::Right)
+            .render(area, buf);
+    }
+}
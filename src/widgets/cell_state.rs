@@ -0,0 +1,25 @@
+use notatin::cell::CellState;
+use ratatui::style::{Color, Style};
+
+/// Short marker shown next to keys and values that are not plain allocated cells.
+pub fn marker(cell_state: &CellState) -> &'static str {
+    match cell_state {
+        CellState::Allocated => "",
+        CellState::DeletedPrimaryFile => "[deleted]",
+        CellState::DeletedPrimaryFileSlack => "[deleted, slack]",
+        CellState::DeletedTransactionLog => "[deleted in log]",
+        CellState::ModifiedTransactionLog => "[modified in log]",
+    }
+}
+
+/// Row style that makes recovered and modified cells stand out.
+pub fn style(cell_state: &CellState) -> Style {
+    match cell_state {
+        CellState::Allocated => Style::default(),
+        CellState::DeletedPrimaryFile | CellState::DeletedPrimaryFileSlack => {
+            Style::default().fg(Color::Red)
+        }
+        CellState::DeletedTransactionLog => Style::default().fg(Color::Magenta),
+        CellState::ModifiedTransactionLog => Style::default().fg(Color::Yellow),
+    }
+}
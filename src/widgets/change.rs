@@ -0,0 +1,43 @@
+use notatin::cell::CellState;
+use ratatui::style::{Color, Style};
+
+use crate::app::compare::Change;
+use crate::widgets::cell_state;
+
+/// Marker shown next to keys and values that differ from the baseline hive.
+pub fn marker(change: Option<Change>, changes_below: bool) -> &'static str {
+    match change {
+        Some(Change::Added) => "[added]",
+        Some(Change::Removed) => "[removed]",
+        Some(Change::Modified) => "[modified]",
+        None if changes_below => "[changes below]",
+        None => "",
+    }
+}
+
+/// Row style for keys and values that differ from the baseline hive.
+pub fn style(change: Option<Change>) -> Style {
+    match change {
+        Some(Change::Added) => Style::default().fg(Color::Green),
+        Some(Change::Removed) => Style::default().fg(Color::Red),
+        Some(Change::Modified) => Style::default().fg(Color::Yellow),
+        None => Style::default(),
+    }
+}
+
+/// Marker and row style for an entry compared with the baseline hive. The
+/// marker of its cell state is kept, so recovered entries stay recognizable.
+pub fn with_cell_state(
+    change: Option<Change>,
+    changes_below: bool,
+    state: &CellState,
+) -> (String, Style) {
+    let markers: Vec<&str> = [cell_state::marker(state), marker(change, changes_below)]
+        .into_iter()
+        .filter(|marker| !marker.is_empty())
+        .collect();
+    (
+        markers.join(" "),
+        cell_state::style(state).patch(style(change)),
+    )
+}
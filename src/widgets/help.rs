@@ -0,0 +1,129 @@
+use crate::app::keymap::{Action, Context, Key, Keymap};
+use crate::app::state::State;
+use itertools::Itertools;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::StatefulWidget;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::*,
+    symbols::border,
+    widgets::{block::*, *},
+};
+
+/// The keys of every action in `contexts`, a section per context. Keys taken
+/// by an earlier context are left out of the later ones, they never get there.
+pub fn help_lines(keymap: &Keymap, contexts: &[Context]) -> Vec<Line<'static>> {
+    let sections: Vec<(Context, Vec<(String, Action)>)> = contexts
+        .iter()
+        .enumerate()
+        .map(|(index, context)| {
+            let actions = keymap
+                .bindings(*context)
+                .map(|(action, keys)| {
+                    let keys: Vec<&Key> = keys
+                        .iter()
+                        .filter(|key| !contexts[..index].iter().any(|c| keymap.binds(*c, key)))
+                        .collect();
+                    let keys = match keys.as_slice() {
+                        [] => "(none)".to_string(),
+                        keys => keys.iter().join(", "),
+                    };
+                    (keys, action)
+                })
+                .collect();
+            (*context, actions)
+        })
+        .collect();
+    let width = sections
+        .iter()
+        .flat_map(|(_, actions)| actions.iter().map(|(keys, _)| keys.chars().count()))
+        .max()
+        .unwrap_or_default();
+
+    let mut lines = vec![];
+    for (context, actions) in sections {
+        if !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::from(context.title()).bold());
+        for (keys, action) in actions {
+            lines.push(Line::from(vec![
+                Span::from(format!("  {:<width$}  ", keys, width = width)).blue(),
+                Span::from(action.description()),
+            ]));
+        }
+    }
+    lines
+}
+
+/// Hints for a title, each label followed by the first key bound to its
+/// action in `context`. Actions without keys are left out.
+pub fn hints(keymap: &Keymap, context: Context, actions: &[(&str, Action)]) -> Line<'static> {
+    let mut spans: Vec<Span> = vec![];
+    for (label, action) in actions {
+        if let Some(key) = keymap.first_key(context, *action) {
+            spans.push(format!(" {} ", label).into());
+            spans.push(format!("<{}>", key).blue().bold());
+        }
+    }
+    spans.push(" ".into());
+    Line::from(spans)
+}
+
+/// Popup listing the keys of the pane or overlay it was opened in.
+pub struct HelpView;
+
+impl StatefulWidget for &mut HelpView {
+    type State = State;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut State)
+    where
+        Self: Sized,
+    {
+        let [_, area, _] = Layout::vertical([
+            Constraint::Percentage(10),
+            Constraint::Percentage(80),
+            Constraint::Percentage(10),
+        ])
+        .areas(area);
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Percentage(15),
+            Constraint::Percentage(70),
+            Constraint::Percentage(15),
+        ])
+        .areas(area);
+
+        let instructions = Title::from(hints(
+            &state.keymap,
+            Context::Help,
+            &[
+                ("Down", Action::Down),
+                ("Up", Action::Up),
+                ("Close", Action::Close),
+            ],
+        ));
+        let block = Block::default()
+            .title(
+                Title::from(format!("keys: {}", state.help.context.title()))
+                    .alignment(Alignment::Center),
+            )
+            .title(
+                instructions
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
+            .borders(Borders::ALL)
+            .border_set(border::THICK)
+            .border_style(Color::Green);
+
+        let lines = help_lines(&state.keymap, &state.help.contexts());
+        let rows = (area.height as usize).saturating_sub(2);
+        state.help.scroll = state.help.scroll.min(lines.len().saturating_sub(rows));
+
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((state.help.scroll as u16, 0))
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,123 @@
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+
+use crate::app::inspector::InspectorState;
+use crate::decoders::filetime;
+use crate::decoders::shell_item::format_guid;
+use crate::decoders::timestamp::DisplayTimezone;
+use crate::hive::utf16_string;
+use crate::hive::{read_u16, read_u32, read_u64};
+
+/// Row widths to choose from, widest first.
+const ROW_WIDTHS: [usize; 3] = [16, 8, 4];
+/// Characters of UTF-16 text shown under the cursor at most.
+const MAX_TEXT_LENGTH: usize = 40;
+/// Number of lines [`interpretation_lines`] returns.
+pub const INTERPRETATION_LINES: usize = 5;
+
+/// Rows of the hex view fitting in `width` columns and `rows` lines, scrolled
+/// so that the cursor is visible. Records the layout in `inspector`.
+pub fn hex_lines(
+    data: &[u8],
+    inspector: &mut InspectorState,
+    width: usize,
+    rows: usize,
+) -> Vec<Line<'static>> {
+    // Offset, two spaces, then three columns per byte in hex and one as text.
+    let bytes_per_row = ROW_WIDTHS
+        .into_iter()
+        .find(|bytes| 11 + 4 * bytes <= width)
+        .unwrap_or(ROW_WIDTHS[ROW_WIDTHS.len() - 1]);
+    let rows = rows.max(1);
+    inspector.cursor = inspector.cursor.min(data.len().saturating_sub(1));
+    inspector.bytes_per_row = bytes_per_row;
+    inspector.page_rows = rows;
+
+    let cursor_row = inspector.cursor / bytes_per_row;
+    if cursor_row < inspector.scroll {
+        inspector.scroll = cursor_row;
+    } else if cursor_row >= inspector.scroll + rows {
+        inspector.scroll = cursor_row + 1 - rows;
+    }
+
+    let selection = inspector.selection();
+    let style = |offset: usize| {
+        if offset == inspector.cursor {
+            Style::new().add_modifier(Modifier::REVERSED)
+        } else if selection.is_some_and(|(start, end)| (start..=end).contains(&offset)) {
+            Style::new().bg(Color::Blue).fg(Color::White)
+        } else {
+            Style::new()
+        }
+    };
+
+    data.chunks(bytes_per_row)
+        .enumerate()
+        .skip(inspector.scroll)
+        .take(rows)
+        .map(|(row, chunk)| {
+            let start = row * bytes_per_row;
+            let mut spans = vec![Span::from(format!("{:08x}  ", start)).dark_gray()];
+            for (column, byte) in chunk.iter().enumerate() {
+                spans.push(Span::styled(format!("{:02x}", byte), style(start + column)));
+                spans.push(Span::from(" "));
+            }
+            spans.push(Span::from(
+                " ".repeat(3 * (bytes_per_row - chunk.len()) + 1),
+            ));
+            for (column, byte) in chunk.iter().enumerate() {
+                let c = if byte.is_ascii_graphic() || *byte == b' ' {
+                    *byte as char
+                } else {
+                    '.'
+                };
+                spans.push(Span::styled(c.to_string(), style(start + column)));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// The position of the cursor or the selection, followed by the bytes at the
+/// cursor read as numbers, a FILETIME, a GUID and UTF-16 text.
+pub fn interpretation_lines(
+    data: &[u8],
+    inspector: &InspectorState,
+    timezone: &DisplayTimezone,
+) -> Vec<Line<'static>> {
+    let cursor = inspector.cursor;
+    let position = match inspector.selection() {
+        Some((start, end)) => format!(
+            "Selected {:#x}-{:#x} ({} bytes)",
+            start,
+            end,
+            end - start + 1
+        ),
+        None => format!("Offset {:#x} of {:#x} bytes", cursor, data.len()),
+    };
+    let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+
+    let numbers = format!(
+        "u16 {}  u32 {}  u64 {}",
+        or_dash(read_u16(data, cursor).map(|n| n.to_string())),
+        or_dash(read_u32(data, cursor).map(|n| n.to_string())),
+        or_dash(read_u64(data, cursor).map(|n| n.to_string())),
+    );
+    let time = read_u64(data, cursor).map(|value| match filetime(value) {
+        Some(time) => timezone.format(time),
+        None => "not a time".to_string(),
+    });
+    let guid = data.get(cursor..cursor + 16).map(format_guid);
+    let text: String = utf16_string(data.get(cursor..).unwrap_or_default())
+        .chars()
+        .take(MAX_TEXT_LENGTH)
+        .collect();
+
+    vec![
+        Line::from(position).bold(),
+        Line::from(numbers),
+        Line::from(format!("FILETIME {}", or_dash(time))),
+        Line::from(format!("GUID {}", or_dash(guid))),
+        Line::from(format!("UTF-16 \"{}\"", text)),
+    ]
+}
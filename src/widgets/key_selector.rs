@@ -1,12 +1,15 @@
-use crate::app::state::{State, FocusedPane};
+use crate::app::keymap::{Action, Context};
+use crate::app::state::{FocusedPane, State};
+use crate::hive::key_path;
+use crate::widgets::help::hints;
+use crate::widgets::{cell_state, change};
 use ratatui::text::Text;
 use ratatui::widgets::StatefulWidget;
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
+    layout::{Constraint, Rect},
     style::*,
     symbols::border,
-    text::Line,
     widgets::{block::*, *},
 };
 
@@ -21,14 +24,11 @@ impl StatefulWidget for &mut KeySelector {
         Self: Sized,
     {
         let title = Title::from("subkeys".to_string());
-        let instructions = Title::from(Line::from(vec![
-            " Enter Subkey ".into(),
-            "<L>".blue().bold(),
-            " Go to parent key ".into(),
-            "<H>".blue().bold(),
-            " Quit ".into(),
-            "<Q> ".blue().bold(),
-        ]));
+        let instructions = Title::from(hints(
+            &state.keymap,
+            Context::Global,
+            &[("Keys", Action::Help), ("Quit", Action::Quit)],
+        ));
         let block = Block::default()
             .title(title.alignment(Alignment::Center))
             .title(
@@ -48,13 +48,27 @@ impl StatefulWidget for &mut KeySelector {
 
         let rows: Vec<Row> = state
             .navigation
-            .current_key
-            .read_sub_keys(&mut state.navigation.parser)
+            .current_subkeys
             .iter()
-            .map(|key| Row::new(vec![Cell::new(key.key_name.clone())]))
+            .map(|key| match &state.navigation.comparison {
+                Some(comparison) => {
+                    let path = key_path::relative(&key.path);
+                    let (marker, style) = change::with_cell_state(
+                        comparison.key_change(&path),
+                        comparison.has_changes(&path),
+                        &key.cell_state,
+                    );
+                    Row::new(vec![Cell::new(key.key_name.clone()), Cell::new(marker)]).style(style)
+                }
+                None => Row::new(vec![
+                    Cell::new(key.key_name.clone()),
+                    Cell::new(cell_state::marker(&key.cell_state)),
+                ])
+                .style(cell_state::style(&key.cell_state)),
+            })
             .collect::<Vec<Row>>();
 
-        let table = Table::new(rows, vec![80])
+        let table = Table::new(rows, vec![Constraint::Fill(1), Constraint::Length(32)])
             .block(block)
             .highlight_style(Style::new().add_modifier(Modifier::BOLD))
             .highlight_symbol(Text::from("|").blue());
@@ -3,10 +3,14 @@ use ratatui::{
     widgets::StatefulWidget,
 };
 
-use crate::app::state::State;
+use crate::app::mouse::HitAreas;
+use crate::app::state::{KeyPreview, State};
 
 use super::{
-    key_selector::KeySelector, value_inspector::ValueInspector, value_selector::ValueSelector,
+    bookmark_picker::BookmarkPicker, breadcrumb::Breadcrumb, help::HelpView,
+    key_selector::KeySelector, prompt_line::PromptLine, status_bar::StatusBar,
+    subkey_preview::SubkeyPreview, timeline::TimelineView, value_inspector::ValueInspector,
+    value_selector::ValueSelector,
 };
 
 pub struct MainWidget {}
@@ -22,27 +26,74 @@ impl StatefulWidget for MainWidget {
     ) where
         Self: Sized,
     {
+        let screen_layout = Layout::new(
+            Direction::Vertical,
+            vec![
+                Constraint::Length(1),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ],
+        );
+
         let main_layout = Layout::new(
             Direction::Horizontal,
             vec![Constraint::Percentage(50), Constraint::Percentage(50)],
         )
         .vertical_margin(2);
 
+        let key_layout = Layout::new(
+            Direction::Vertical,
+            vec![
+                Constraint::Min(0),
+                match state.key_preview {
+                    KeyPreview::Details => Constraint::Length(10),
+                    KeyPreview::Security => Constraint::Percentage(50),
+                },
+            ],
+        );
+
         let value_layout = Layout::new(
             Direction::Vertical,
             vec![Constraint::Percentage(40), Constraint::Percentage(60)],
         )
         .horizontal_margin(2);
 
-        let main_rects = main_layout.split(area);
+        let screen_rects = screen_layout.split(area);
+        let main_rects = main_layout.split(screen_rects[1]);
+        let key_rects = key_layout.split(main_rects[0]);
         let value_rects = value_layout.split(main_rects[1]);
 
         let mut key_selector = KeySelector {};
+        let mut subkey_preview = SubkeyPreview {};
         let mut value_selector = ValueSelector {};
         let mut value_inspector = ValueInspector {};
+        let mut breadcrumb = Breadcrumb {};
+        let mut status_bar = StatusBar {};
+        let mut prompt_line = PromptLine {};
 
-        key_selector.render(main_rects[0], buf, state);
-        value_selector.render(value_rects[0], buf, state);
-        value_inspector.render(value_rects[1], buf, state);
+        state.mouse.areas = HitAreas::default();
+        breadcrumb.render(screen_rects[0], buf, state);
+        if state.timeline.visible {
+            TimelineView.render(screen_rects[1], buf, state);
+        } else {
+            state.mouse.areas.key_selector = key_rects[0];
+            state.mouse.areas.value_selector = value_rects[0];
+            state.mouse.areas.value_inspector = value_rects[1];
+            key_selector.render(key_rects[0], buf, state);
+            subkey_preview.render(key_rects[1], buf, state);
+            value_selector.render(value_rects[0], buf, state);
+            value_inspector.render(value_rects[1], buf, state);
+        }
+        if state.bookmarks.visible {
+            BookmarkPicker.render(screen_rects[1], buf, state);
+        }
+        if state.help.visible {
+            HelpView.render(screen_rects[1], buf, state);
+        }
+        if state.prompt.is_some() {
+            prompt_line.render(screen_rects[2], buf, state);
+        } else {
+            status_bar.render(screen_rects[2], buf, state);
+        }
     }
 }
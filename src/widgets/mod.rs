@@ -1,5 +1,15 @@
+pub mod bookmark_picker;
+pub mod breadcrumb;
+pub mod cell_state;
+pub mod change;
+pub mod help;
+pub mod hex_view;
 pub mod key_selector;
+pub mod main;
+pub mod prompt_line;
+pub mod status_bar;
 pub mod subkey_preview;
-pub mod value_selector;
+pub mod timeline;
+pub mod value_diff;
 pub mod value_inspector;
-pub mod main;
+pub mod value_selector;
@@ -0,0 +1,50 @@
+use crate::app::prompt::PromptKind;
+use crate::app::state::State;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::StatefulWidget;
+use ratatui::{buffer::Buffer, layout::Rect, style::*, widgets::*};
+
+/// The prompt being typed, drawn over the status bar.
+pub struct PromptLine;
+
+impl StatefulWidget for &mut PromptLine {
+    type State = State;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut State)
+    where
+        Self: Sized,
+    {
+        let Some(prompt) = &state.prompt else {
+            return;
+        };
+
+        let mut spans: Vec<Span> = vec![
+            Span::from(prompt.label()).blue().bold(),
+            Span::from(prompt.input.clone()),
+            Span::from("_").slow_blink(),
+        ];
+        if prompt.kind == PromptKind::Search {
+            let options = state.search.options;
+            let toggle = |name: &'static str, on: bool| {
+                if on {
+                    Span::from(name).green().bold()
+                } else {
+                    Span::from(name).dark_gray()
+                }
+            };
+            spans.extend([
+                "   ".into(),
+                toggle("regex", options.regex),
+                " <C-r> ".blue(),
+                toggle("case", options.case_sensitive),
+                " <C-t>".blue(),
+            ]);
+        }
+
+        if !prompt.completions.is_empty() {
+            spans.push("   ".into());
+            spans.push(Span::from(prompt.completions.join("  ")).dark_gray());
+        }
+
+        Paragraph::new(Line::from(spans)).render(area, buf);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::app::state::State;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::StatefulWidget;
+use ratatui::{buffer::Buffer, layout::Rect, style::*, widgets::*};
+
+/// One-line summary of the opened hive at the bottom of the screen.
+pub struct StatusBar;
+
+impl StatefulWidget for &mut StatusBar {
+    type State = State;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut State)
+    where
+        Self: Sized,
+    {
+        let replay = &state.log_replay;
+        let mut spans: Vec<Span> = vec![
+            Span::from(format!(" {} ", state.source.path.display())).bold(),
+            " | ".into(),
+        ];
+
+        if replay.applied() {
+            spans.push(
+                format!(
+                    "transaction logs applied up to sequence number {}, {} sequence number(s) recovered",
+                    replay.last_sequence_number.unwrap_or_default(),
+                    replay.recovered_sequence_numbers()
+                )
+                .green(),
+            );
+        } else if replay.dirty && replay.logs.is_empty() {
+            spans.push("dirty hive, no transaction logs found".yellow());
+        } else if replay.dirty {
+            spans.push("dirty hive, transaction logs had nothing to replay".yellow());
+        } else {
+            spans.push("clean hive, no transaction logs applied".into());
+        }
+        if !replay.skipped.is_empty() {
+            spans.push(
+                format!(" ({} log file(s) or entries skipped)", replay.skipped.len()).yellow(),
+            );
+        }
+
+        if state.source.recover_deleted {
+            spans.push(" | ".into());
+            spans.push("recovering deleted cells".magenta());
+        }
+
+        if let Some(comparison) = &state.navigation.comparison {
+            let [added, removed, modified] = comparison.counts;
+            spans.push(" | ".into());
+            spans.push(Span::from(format!(
+                "compared with {}: ",
+                comparison.baseline.path.display()
+            )));
+            spans.push(format!("{} added", added).green());
+            spans.push(", ".into());
+            spans.push(format!("{} removed", removed).red());
+            spans.push(", ".into());
+            spans.push(format!("{} modified", modified).yellow());
+            if state.navigation.changes_only {
+                spans.push(" (changes only)".into());
+            }
+        } else if let Some(pending) = &state.navigation.pending_comparison {
+            spans.push(" | ".into());
+            spans.push(format!("comparing with {}…", pending.baseline.path.display()).dark_gray());
+        }
+
+        let search = &state.search;
+        if !search.query.is_empty() {
+            spans.push(" | ".into());
+            spans.push(Span::from(format!(
+                "/{}: {}{} hit(s){}",
+                search.query,
+                search
+                    .current
+                    .map(|index| format!("{}/", index + 1))
+                    .unwrap_or_default(),
+                search.hits.len(),
+                if search.running { ", searching..." } else { "" }
+            )));
+            if let Some(error) = &search.error {
+                spans.push(Span::from(format!(" {}", error)).red());
+            }
+        }
+
+        if let Some(message) = &state.message {
+            spans.push(" | ".into());
+            spans.push(Span::from(message.clone()).yellow());
+        }
+
+        Paragraph::new(Line::from(spans)).render(area, buf);
+    }
+}
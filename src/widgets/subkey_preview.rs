@@ -0,0 +1,149 @@
+use crate::app::state::{KeyPreview, State};
+use crate::hive::security::{Ace, SecurityDescriptor, Sid};
+use crate::widgets::cell_state;
+use notatin::cell_key_node::CellKeyNode;
+use notatin::log::Logs;
+use notatin::parser::Parser;
+use ratatui::prelude::Alignment;
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::StatefulWidget;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::*,
+    widgets::{block::*, *},
+};
+
+/// Shows the metadata or the security descriptor of the selected subkey.
+pub struct SubkeyPreview;
+
+impl StatefulWidget for &mut SubkeyPreview {
+    type State = State;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut State)
+    where
+        Self: Sized,
+    {
+        let title = Title::from(match state.key_preview {
+            KeyPreview::Details => "key details",
+            KeyPreview::Security => "key security",
+        });
+        let block = Block::default()
+            .title(title.alignment(Alignment::Center))
+            .borders(Borders::ALL);
+
+        let content: Text = match state.navigation.selected_subkey {
+            Some(ref key) => match state.key_preview {
+                KeyPreview::Details => Text::from(details_lines(key)),
+                KeyPreview::Security => {
+                    Text::from(security_lines(key, &mut state.navigation.parser))
+                }
+            },
+            None => Text::from("no subkey selected"),
+        };
+
+        Paragraph::new(content)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+}
+
+fn field(name: &str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::from(format!("{:<14}", name)).bold(),
+        Span::from(value),
+    ])
+}
+
+fn details_lines(key: &CellKeyNode) -> Vec<Line<'static>> {
+    let detail = &key.detail;
+    // notatin does not read class names, only where they are stored.
+    let class_name = match detail.class_name_size() {
+        0 => "-".to_string(),
+        size => format!(
+            "{} bytes in cell {:#010x}",
+            size,
+            detail.class_name_offset_relative()
+        ),
+    };
+    vec![
+        field(
+            "Last written",
+            key.last_key_written_date_and_time()
+                .format("%Y-%m-%d %H:%M:%S%.3f UTC")
+                .to_string(),
+        ),
+        field("Offset", format!("{:#010x}", key.file_offset_absolute)),
+        field(
+            "Flags",
+            format!("{:?}", key.key_node_flags(&mut Logs::default())),
+        ),
+        field("Class name", class_name),
+        field(
+            "Subkeys",
+            format!(
+                "{} ({} volatile)",
+                detail.number_of_sub_keys(),
+                detail.number_of_volatile_sub_keys()
+            ),
+        ),
+        field("Values", detail.number_of_key_values().to_string()),
+        field(
+            "Security",
+            format!("cell {:#010x}", detail.security_key_offset_relative()),
+        ),
+        field("Cell state", format!("{:?}", key.cell_state))
+            .style(cell_state::style(&key.cell_state)),
+    ]
+}
+
+fn security_lines(key: &CellKeyNode, parser: &mut Parser) -> Vec<Line<'static>> {
+    let descriptors = key.clone().get_security_descriptors(parser);
+    let Some(descriptor) = descriptors
+        .ok()
+        .and_then(|descriptors| descriptors.first().map(SecurityDescriptor::from))
+    else {
+        return vec![Line::from("no readable security descriptor")];
+    };
+
+    let trustee = |sid: &Option<Sid>| sid.as_ref().map_or("-".to_string(), Sid::describe);
+
+    let mut lines = vec![
+        field("Owner", trustee(&descriptor.owner)),
+        field("Group", trustee(&descriptor.group)),
+        field("SDDL", descriptor.to_sddl()),
+    ];
+    lines.extend(acl_lines("DACL", &descriptor.dacl));
+    lines.extend(acl_lines("SACL", &descriptor.sacl));
+    lines
+}
+
+fn acl_lines(name: &str, acl: &Option<Vec<Ace>>) -> Vec<Line<'static>> {
+    let Some(aces) = acl else {
+        return vec![field(name, "not present".to_string())];
+    };
+    if aces.is_empty() {
+        return vec![field(name, "empty".to_string())];
+    }
+
+    let mut lines = vec![Line::from(format!(
+        "{:<14}{:<7}{:<8}{:<28}Trustee",
+        name, "Type", "Flags", "Rights"
+    ))
+    .bold()];
+    lines.extend(aces.iter().map(|ace| {
+        let line = Line::from(format!(
+            "{:<14}{:<7}{:<8}{:<28}{}",
+            "",
+            ace.type_name(),
+            ace.flags_code(),
+            ace.rights_description(),
+            ace.sid.as_ref().map_or("-".to_string(), Sid::describe)
+        ));
+        match ace.type_name() {
+            "Deny" => line.red(),
+            _ => line,
+        }
+    }));
+    lines
+}
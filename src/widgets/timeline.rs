@@ -0,0 +1,107 @@
+use crate::app::keymap::{Action, Context};
+use crate::app::state::State;
+use crate::widgets::help::hints;
+use ratatui::text::Text;
+use ratatui::widgets::StatefulWidget;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Rect},
+    style::*,
+    symbols::border,
+    widgets::{block::*, *},
+};
+
+/// Every key of the hive ordered by when it was last written, drawn over the panes.
+pub struct TimelineView;
+
+impl StatefulWidget for &mut TimelineView {
+    type State = State;
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut State)
+    where
+        Self: Sized,
+    {
+        let timeline = &mut state.timeline;
+        let mut title = format!(
+            "timeline: {} of {} keys, {}",
+            timeline.shown.len(),
+            timeline.entries.len(),
+            timeline.order.label()
+        );
+        if !timeline.filter.path.is_empty() {
+            title.push_str(&format!(", path contains \"{}\"", timeline.filter.path));
+        }
+        let range = timeline.filter.range();
+        if !range.is_empty() {
+            title.push_str(&format!(", written {}", range));
+        }
+        let instructions = Title::from(hints(
+            &state.keymap,
+            Context::Timeline,
+            &[
+                ("Open Key", Action::Open),
+                ("Order", Action::CycleOrder),
+                ("Path Filter", Action::FilterPath),
+                ("Time Range", Action::FilterRange),
+                ("Close", Action::Close),
+                ("Keys", Action::Help),
+            ],
+        ));
+        let block = Block::default()
+            .title(Title::from(title).alignment(Alignment::Center))
+            .title(
+                instructions
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
+            .borders(Borders::ALL)
+            .border_set(border::THICK)
+            .border_style(Color::Green);
+
+        if timeline.loading || timeline.error.is_some() {
+            let text = match &timeline.error {
+                Some(error) => Text::from(error.clone()).red(),
+                None => Text::from("collecting key times..."),
+            };
+            Paragraph::new(text).block(block).render(area, buf);
+            return;
+        }
+
+        // The timeline lists every key of the hive, so only the rows that fit are
+        // built. The table is given just those, along with the selection in them.
+        let height = (area.height as usize).saturating_sub(3).max(1);
+        let selected = timeline.table_state.selected().unwrap_or(0);
+        let offset = timeline
+            .table_state
+            .offset()
+            .clamp(selected.saturating_sub(height - 1), selected);
+        *timeline.table_state.offset_mut() = offset;
+        let rows: Vec<Row> = timeline
+            .shown
+            .iter()
+            .skip(offset)
+            .take(height)
+            .map(|index| {
+                let entry = &timeline.entries[*index];
+                Row::new(vec![
+                    Cell::new(
+                        entry
+                            .last_written
+                            .format("%Y-%m-%d %H:%M:%S%.3f")
+                            .to_string(),
+                    ),
+                    Cell::new(entry.key_path.clone()),
+                ])
+            })
+            .collect();
+        let mut window = TableState::default()
+            .with_selected(timeline.table_state.selected().map(|index| index - offset));
+
+        let table = Table::new(rows, vec![Constraint::Length(25), Constraint::Fill(1)])
+            .header(Row::new(vec!["Last written (UTC)", "Key"]).bold())
+            .block(block)
+            .highlight_style(Style::new().add_modifier(Modifier::BOLD))
+            .highlight_symbol(Text::from("|").blue());
+
+        <Table as StatefulWidget>::render(table, area, buf, &mut window);
+    }
+}
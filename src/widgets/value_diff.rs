@@ -0,0 +1,69 @@
+use notatin::cell_key_value::CellKeyValue;
+use notatin::cell_value::CellValue;
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+
+use crate::widgets::value_selector::ValueCellPreview;
+
+const BYTES_PER_ROW: usize = 16;
+
+/// Renders the differences between an `old` and a `new` value as styled lines.
+/// Lines only present in `old` are red, lines only present in `new` are green.
+pub fn diff_lines(old: &CellKeyValue, new: &CellKeyValue) -> Vec<Line<'static>> {
+    let (old_type, new_type) = (old.data_type, new.data_type);
+    let (old, new) = (old.get_content().0, new.get_content().0);
+    match (&old, &new) {
+        (CellValue::Binary(old), CellValue::Binary(new)) => {
+            let mut lines = vec![Line::from("old:".red().bold())];
+            lines.extend(hex_diff_lines(old, new, Color::Red));
+            lines.push(Line::from("new:".green().bold()));
+            lines.extend(hex_diff_lines(new, old, Color::Green));
+            lines
+        }
+        (CellValue::MultiString(old), CellValue::MultiString(new)) => {
+            let mut lines: Vec<Line> = old
+                .iter()
+                .map(|s| {
+                    if new.contains(s) {
+                        Line::from(format!("  \"{}\"", s))
+                    } else {
+                        Line::from(format!("- \"{}\"", s)).red()
+                    }
+                })
+                .collect();
+            lines.extend(
+                new.iter()
+                    .filter(|s| !old.contains(s))
+                    .map(|s| Line::from(format!("+ \"{}\"", s)).green()),
+            );
+            lines
+        }
+        _ if old == new && old_type == new_type => vec![Line::from(format!(
+            "  {} (unchanged)",
+            ValueCellPreview(old_type, old.clone())
+        ))],
+        _ => vec![
+            Line::from(format!("- {}", ValueCellPreview(old_type, old.clone()))).red(),
+            Line::from(format!("+ {}", ValueCellPreview(new_type, new.clone()))).green(),
+        ],
+    }
+}
+
+/// Hex dump of `data` in which bytes that differ from `other` are highlighted in `color`.
+fn hex_diff_lines(data: &[u8], other: &[u8], color: Color) -> Vec<Line<'static>> {
+    data.chunks(BYTES_PER_ROW)
+        .enumerate()
+        .map(|(row, chunk)| {
+            let mut spans = vec![Span::from(format!("{:08x}  ", row * BYTES_PER_ROW))];
+            spans.extend(chunk.iter().enumerate().map(|(column, byte)| {
+                let span = Span::from(format!("{:02x} ", byte));
+                if other.get(row * BYTES_PER_ROW + column) == Some(byte) {
+                    span
+                } else {
+                    span.style(Style::default().fg(color).bold())
+                }
+            }));
+            Line::from(spans)
+        })
+        .collect()
+}
@@ -1,11 +1,20 @@
+use crate::app::compare::Change;
+use crate::app::inspector::{version_source, versions};
+use crate::app::keymap::{Action, Context};
 use crate::app::state::FocusedPane;
 use crate::app::state::State;
+use crate::decoders::resources::{self, Node};
+use crate::decoders::{self, timestamp};
+use crate::export::stored_data;
+use crate::widgets::help::hints;
+use crate::widgets::hex_view;
+use crate::widgets::value_diff::diff_lines;
+use crate::widgets::value_selector::{type_name, ValueCellPreview};
 
-use hxdmp::hexdump;
-
+use notatin::cell_key_value::{CellKeyValue, CellKeyValueDataTypes};
 use notatin::cell_value::CellValue;
 use ratatui::prelude::Alignment;
-use ratatui::text::Text;
+use ratatui::text::{Line, Text};
 use ratatui::widgets::StatefulWidget;
 use ratatui::{
     style::*,
@@ -24,9 +33,19 @@ impl StatefulWidget for &mut ValueInspector {
         state: &mut Self::State,
     ) {
         let title = Title::from("Value Inspector".to_string());
+        let instructions = Title::from(hints(
+            &state.keymap,
+            Context::Global,
+            &[("Keys", Action::Help)],
+        ));
 
         let block = Block::default()
             .title(title.alignment(Alignment::Center))
+            .title(
+                instructions
+                    .alignment(Alignment::Center)
+                    .position(Position::Bottom),
+            )
             .borders(Borders::ALL)
             .border_set(match state.focused_pane {
                 FocusedPane::ValueInspector => border::THICK,
@@ -38,18 +57,150 @@ impl StatefulWidget for &mut ValueInspector {
             });
 
         let content: Text = match state.navigation.selected_value {
-            Some(ref value) => Text::from(format!(
-                "Data Type: {:?}\nValue Data: {}",
-                value.data_type,
-                match value.get_content().0 {
-                    CellValue::Binary(blob) => {
-                        let mut dump: Vec<u8> = Vec::new();
-                        let _ = hexdump(&blob, &mut dump);
-                        String::from_utf8(dump).unwrap()
+            Some(ref value) => {
+                state.inspector.sync(value);
+                let versions = versions(value);
+                let index = std::cmp::min(state.inspector.version, versions.len() - 1);
+                let version = versions[index];
+
+                let mut content = Text::from(vec![Line::from(format!(
+                    "Data Type: {} ({})",
+                    type_name(version.data_type),
+                    version.data_type as u32
+                ))]);
+                if versions.len() == 1 {
+                    content
+                        .lines
+                        .push(Line::from(format!("Read from: {}", version_source(value))));
+                } else {
+                    content
+                        .lines
+                        .push(Line::from(format!("Versions ({}):", versions.len())));
+                    content.lines.extend(version_lines(&versions, index));
+                }
+                let path = state.navigation.selected_path();
+                if let Some(time) =
+                    timestamp::detect(&path, &value.detail.value_name(), &version.get_content().0)
+                {
+                    content.lines.push(Line::from(format!(
+                        "Timestamp: {} ({})",
+                        state.timezone.format(time.time),
+                        time.source()
+                    )));
+                }
+                if let (true, Some(environment), CellValue::String(text)) = (
+                    state.inspector.expand
+                        && version.data_type == CellKeyValueDataTypes::REG_EXPAND_SZ,
+                    &state.environment,
+                    version.get_content().0,
+                ) {
+                    content.lines.push(Line::from(format!(
+                        "Expanded: \"{}\"",
+                        environment.expand(&text)
+                    )));
+                }
+                let comparison = state.navigation.comparison.as_ref();
+                let mut baseline = None;
+                if let Some(comparison) = comparison {
+                    match comparison.value_change(&path, &value.detail.value_name()) {
+                        Some(Change::Added) => content
+                            .lines
+                            .push(Line::from("Not in the baseline hive").green()),
+                        Some(Change::Removed) => content
+                            .lines
+                            .push(Line::from("Only in the baseline hive").red()),
+                        Some(Change::Modified) => {
+                            baseline = comparison.baseline_value(&path, &value.detail.value_name())
+                        }
+                        None => {}
+                    }
+                }
+
+                if let (0, Some(baseline)) = (index, baseline) {
+                    content
+                        .lines
+                        .push(Line::from("Changes from the baseline hive to this one:"));
+                    content.lines.extend(diff_lines(baseline, value));
+                } else if index == 0 && state.inspector.content {
+                    let sniffed = decoders::content::sniff(&value.get_content().0);
+                    content.lines.push(Line::from(if sniffed.layers.is_empty() {
+                        "No embedded content found".to_string()
+                    } else {
+                        let kinds: Vec<&str> = sniffed
+                            .layers
+                            .iter()
+                            .map(|layer| layer.kind.label())
+                            .collect();
+                        format!("Content: {}", kinds.join(" → "))
+                    }));
+                    for url in &sniffed.urls {
+                        content.lines.push(Line::from(format!("URL: {}", url)));
+                    }
+                    for address in &sniffed.addresses {
+                        content
+                            .lines
+                            .push(Line::from(format!("IP address: {}", address)));
+                    }
+                    let width = (area.width as usize).saturating_sub(2);
+                    let rows = (area.height as usize).saturating_sub(2 + content.lines.len());
+                    match sniffed.innermost().and_then(printable_text) {
+                        Some(text) => content.lines.extend(text_lines(text, width, rows)),
+                        None => {
+                            let data = state.inspector.data(value);
+                            content.lines.extend(hex_view::hex_lines(
+                                &data,
+                                &mut state.inspector,
+                                width,
+                                rows.saturating_sub(hex_view::INTERPRETATION_LINES),
+                            ));
+                            content.lines.extend(hex_view::interpretation_lines(
+                                &data,
+                                &state.inspector,
+                                &state.timezone,
+                            ));
+                        }
+                    }
+                } else if let Some(decoded) = (index == 0 && !state.inspector.raw)
+                    .then(|| decoded_lines(&path, value))
+                    .flatten()
+                {
+                    content.lines.extend(decoded);
+                } else if index == 0 {
+                    let data = value.get_content().0;
+                    if state.inspector.raw || matches!(data, CellValue::Binary(_)) {
+                        let data = stored_data(value);
+                        content
+                            .lines
+                            .push(Line::from(format!("Value Data: {} bytes", data.len())));
+                        let rows = (area.height as usize).saturating_sub(
+                            2 + content.lines.len() + hex_view::INTERPRETATION_LINES,
+                        );
+                        let width = (area.width as usize).saturating_sub(2);
+                        content.lines.extend(hex_view::hex_lines(
+                            &data,
+                            &mut state.inspector,
+                            width,
+                            rows,
+                        ));
+                        content.lines.extend(hex_view::interpretation_lines(
+                            &data,
+                            &state.inspector,
+                            &state.timezone,
+                        ));
+                    } else {
+                        content.lines.push(Line::from(format!(
+                            "Value Data: {}",
+                            ValueCellPreview::of(value)
+                        )));
                     }
-                    _ => format!("{:?}", value.get_content().0),
+                } else {
+                    content
+                        .lines
+                        .push(Line::from("Changes from this version to the current data:"));
+                    content.lines.extend(diff_lines(version, value));
                 }
-            )),
+                content
+            }
             None => Text::from(""),
         };
 
@@ -58,3 +209,86 @@ impl StatefulWidget for &mut ValueInspector {
         <Paragraph as Widget>::render(display, area, buf);
     }
 }
+
+/// One line per version of a value, newest first, with the selected one highlighted.
+fn version_lines(versions: &[&CellKeyValue], selected: usize) -> Vec<Line<'static>> {
+    versions
+        .iter()
+        .enumerate()
+        .map(|(index, version)| {
+            let line = Line::from(format!(
+                "{} {:>3}. {}{}: {}",
+                if index == selected { ">" } else { " " },
+                index + 1,
+                version_source(version),
+                if index == 0 { " (current)" } else { "" },
+                ValueCellPreview::of(version)
+            ));
+            if index == selected {
+                line.reversed()
+            } else {
+                line
+            }
+        })
+        .collect()
+}
+
+/// Lines describing a value that one of the decoders understands, if any.
+fn decoded_lines(path: &str, value: &CellKeyValue) -> Option<Vec<Line<'static>>> {
+    let content = value.get_content().0;
+    if let Some(nodes) = resources::decode(value.data_type, &stored_data(value)) {
+        let mut lines = vec![Line::from(format!(
+            "Decoded as {}:",
+            type_name(value.data_type)
+        ))];
+        lines.extend(nodes.iter().flat_map(Node::lines).map(Line::from));
+        return Some(lines);
+    }
+
+    let decoded = decoders::decode(path, &value.detail.value_name(), &content)?;
+    let mut lines = vec![Line::from(format!("Decoded as {}:", decoded.decoder))];
+    lines.extend(
+        decoded
+            .fields
+            .iter()
+            .map(|field| Line::from(format!("{:<20}{}", field.name, field.value))),
+    );
+    Some(lines)
+}
+
+/// `data` as a string if it is UTF-8 text without control characters other than line breaks and tabs.
+fn printable_text(data: &[u8]) -> Option<&str> {
+    let text = std::str::from_utf8(data).ok()?;
+    text.chars()
+        .all(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .then_some(text)
+}
+
+/// The lines of `text` broken at `width` characters, no more than `rows` of
+/// them. The last row says how many lines did not fit.
+fn text_lines(text: &str, width: usize, rows: usize) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line> = text
+        .lines()
+        .flat_map(|line| {
+            let chars: Vec<char> = line.replace('\t', "    ").chars().collect();
+            let chunks: Vec<String> = chars
+                .chunks(width.max(1))
+                .map(|chunk| chunk.iter().collect())
+                .collect();
+            if chunks.is_empty() {
+                vec![String::new()]
+            } else {
+                chunks
+            }
+        })
+        .map(Line::from)
+        .collect();
+    if lines.len() > rows {
+        let hidden = lines.len() + 1 - rows.max(1);
+        lines.truncate(rows.saturating_sub(1));
+        lines.push(
+            Line::from(format!("… {} more lines, <s> saves all of them", hidden)).dark_gray(),
+        );
+    }
+    lines
+}
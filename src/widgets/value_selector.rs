@@ -1,6 +1,11 @@
 use std::fmt::Display;
 
+use crate::app::keymap::{Action, Context};
 use crate::app::state::{FocusedPane, State};
+use crate::decoders::timestamp;
+use crate::widgets::help::hints;
+use crate::widgets::{cell_state, change};
+use notatin::cell_key_value::{CellKeyValue, CellKeyValueDataTypes};
 use notatin::cell_value::CellValue;
 use ratatui::prelude::Alignment;
 use ratatui::style::Color;
@@ -9,32 +14,58 @@ use ratatui::widgets::StatefulWidget;
 use ratatui::{
     style::*,
     symbols::border,
-    text::Line,
     widgets::{block::*, *},
 };
 
-pub struct ValueCellPreview(CellValue);
+/// Canonical name of a registry data type.
+pub fn type_name(data_type: CellKeyValueDataTypes) -> &'static str {
+    match data_type {
+        CellKeyValueDataTypes::REG_NONE => "REG_NONE",
+        CellKeyValueDataTypes::REG_SZ => "REG_SZ",
+        CellKeyValueDataTypes::REG_EXPAND_SZ => "REG_EXPAND_SZ",
+        CellKeyValueDataTypes::REG_BIN => "REG_BINARY",
+        CellKeyValueDataTypes::REG_DWORD => "REG_DWORD",
+        CellKeyValueDataTypes::REG_DWORD_BIG_ENDIAN => "REG_DWORD_BIG_ENDIAN",
+        CellKeyValueDataTypes::REG_LINK => "REG_LINK",
+        CellKeyValueDataTypes::REG_MULTI_SZ => "REG_MULTI_SZ",
+        CellKeyValueDataTypes::REG_RESOURCE_LIST => "REG_RESOURCE_LIST",
+        CellKeyValueDataTypes::REG_FULL_RESOURCE_DESCRIPTOR => "REG_FULL_RESOURCE_DESCRIPTOR",
+        CellKeyValueDataTypes::REG_RESOURCE_REQUIREMENTS_LIST => "REG_RESOURCE_REQUIREMENTS_LIST",
+        CellKeyValueDataTypes::REG_QWORD => "REG_QWORD",
+        CellKeyValueDataTypes::REG_FILETIME => "REG_FILETIME",
+        _ => "REG_UNKNOWN",
+    }
+}
+
+/// One-line summary of a value's data, labelled with its data type.
+pub struct ValueCellPreview(pub CellKeyValueDataTypes, pub CellValue);
+
+impl ValueCellPreview {
+    pub fn of(value: &CellKeyValue) -> Self {
+        ValueCellPreview(value.data_type, value.get_content().0)
+    }
+}
 
 impl Display for ValueCellPreview {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match &self.0 {
-            CellValue::None => f.write_str("<NO VALUE>"),
-            CellValue::U32(val) => f.write_str(format!("REG_DWORD: {}", val).as_str()),
-            CellValue::U64(val) => f.write_str(format!("REG_QWORD: {}", val).as_str()),
-            CellValue::I32(val) => f.write_str(format!("REG_DWORD: {}", val).as_str()),
-            CellValue::I64(val) => f.write_str(format!("REG_QWORD: {}", val).as_str()),
-            CellValue::String(s) => f.write_str(format!("REG_SZ: \"{}\"", s.clone()).as_str()),
-            CellValue::Error => f.write_str("ERROR DECODING VALUE"),
-            CellValue::Binary(_) => f.write_str("BINARY DATA"),
-            CellValue::MultiString(strs) => f.write_str(
-                format!(
-                    "REG_MULTI_SZ: {}",
-                    strs.iter()
-                        .map(|s| format!("\"{}\"", s))
-                        .collect::<Vec<String>>()
-                        .join(", ")
-                )
-                .as_str(),
+        let name = type_name(self.0);
+        match (self.0, &self.1) {
+            (_, CellValue::None) => write!(f, "{}: <NO VALUE>", name),
+            (_, CellValue::Error) => f.write_str("ERROR DECODING VALUE"),
+            (_, CellValue::U32(val)) => write!(f, "{}: {}", name, val),
+            (_, CellValue::U64(val)) => write!(f, "{}: {}", name, val),
+            (_, CellValue::I32(val)) => write!(f, "{}: {}", name, val),
+            (_, CellValue::I64(val)) => write!(f, "{}: {}", name, val),
+            (_, CellValue::String(s)) => write!(f, "{}: \"{}\"", name, s),
+            (_, CellValue::Binary(bytes)) => write!(f, "{}: {} bytes", name, bytes.len()),
+            (_, CellValue::MultiString(strs)) => write!(
+                f,
+                "{}: {}",
+                name,
+                strs.iter()
+                    .map(|s| format!("\"{}\"", s))
+                    .collect::<Vec<String>>()
+                    .join(", ")
             ),
         }
     }
@@ -51,12 +82,11 @@ impl StatefulWidget for &mut ValueSelector {
         state: &mut Self::State,
     ) {
         let title = Title::from("values".to_string());
-        let instructions = Title::from(Line::from(vec![
-            " Next Value ".into(),
-            "<j>".blue().bold(),
-            " Previous Value ".into(),
-            "<k>".blue().bold(),
-        ]));
+        let instructions = Title::from(hints(
+            &state.keymap,
+            Context::Global,
+            &[("Keys", Action::Help)],
+        ));
         let block = Block::default()
             .title(title.alignment(Alignment::Center))
             .title(
@@ -74,19 +104,45 @@ impl StatefulWidget for &mut ValueSelector {
                 _ => Color::default(),
             });
 
+        let path = state.navigation.selected_path();
+        let key = state.navigation.selected_key().file_offset_absolute;
         let rows: Vec<Row> = state
             .navigation
             .current_values
             .iter()
             .map(|value| {
-                Row::new(vec![
-                    Cell::new(value.get_pretty_name()),
-                    Cell::new(ValueCellPreview(value.get_content().0).to_string()),
-                ])
+                let content = value.get_content().0;
+                let mut preview = ValueCellPreview(value.data_type, content.clone()).to_string();
+                if let Some(time) = timestamp::detect(&path, &value.detail.value_name(), &content) {
+                    preview += &format!(" ({})", state.timezone.format(time.time));
+                }
+                let flags = state
+                    .content_flags
+                    .flags(key, value.file_offset_absolute, &content);
+                let cells = |marker: String| {
+                    vec![
+                        Cell::new(value.get_pretty_name()),
+                        Cell::new(preview.clone()),
+                        Cell::new(flags.clone()).red(),
+                        Cell::new(marker),
+                    ]
+                };
+                match &state.navigation.comparison {
+                    Some(comparison) => {
+                        let (marker, style) = change::with_cell_state(
+                            comparison.value_change(&path, &value.detail.value_name()),
+                            false,
+                            &value.cell_state,
+                        );
+                        Row::new(cells(marker)).style(style)
+                    }
+                    None => Row::new(cells(cell_state::marker(&value.cell_state).to_string()))
+                        .style(cell_state::style(&value.cell_state)),
+                }
             })
             .collect::<Vec<Row>>();
 
-        let table = Table::new(rows, vec![80, 80])
+        let table = Table::new(rows, vec![80, 80, 12, 32])
             .block(block)
             .highlight_style(Style::new().add_modifier(Modifier::BOLD))
             .highlight_symbol(Text::from("|").blue());